@@ -1,9 +1,17 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
-use gts::GtsOps;
+use gts::{
+    CastDirection, CastOptions, GtsConfig, GtsEntityCastResult, GtsError, GtsFileReader, GtsID,
+    GtsIdSegment, GtsInvalidId, GtsOps, GtsReader,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use crate::gen_schemas::generate_schemas_from_rust;
+use crate::logging::{init_logging, LogFormat};
 use crate::server::GtsHttpServer;
 
 #[derive(Parser)]
@@ -14,6 +22,14 @@ struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Log level (error, warn, info, debug, trace); overrides -v
+    #[arg(long)]
+    log_level: Option<tracing::Level>,
+
+    /// Log output format
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
     /// Path to optional GTS config JSON to override defaults
     #[arg(long)]
     config: Option<String>,
@@ -28,10 +44,16 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Validate a GTS ID format
+    /// Validate a GTS ID format and print its parsed segments
     ValidateId {
+        /// GTS ID to validate
+        #[arg(required_unless_present = "gts_id", conflicts_with = "gts_id")]
+        id: Option<String>,
         #[arg(long)]
-        gts_id: String,
+        gts_id: Option<String>,
+        /// Print the parsed ID as JSON instead of a table
+        #[arg(long)]
+        json: bool,
     },
     /// Parse a GTS ID into its components
     ParseId {
@@ -71,10 +93,25 @@ enum Commands {
     },
     /// Cast an instance or schema to a target schema
     Cast {
-        #[arg(long)]
+        #[arg(long, alias = "from")]
         from_id: String,
-        #[arg(long)]
+        #[arg(long, alias = "to")]
         to_schema_id: String,
+        /// Instance JSON file to cast instead of looking up `--from-id` under `--path`
+        #[arg(long, requires_all = ["from_schema", "to_schema"])]
+        instance: Option<PathBuf>,
+        /// Source schema JSON file (with `--instance`)
+        #[arg(long, requires = "instance")]
+        from_schema: Option<PathBuf>,
+        /// Target schema JSON file (with `--instance`)
+        #[arg(long, requires = "instance")]
+        to_schema: Option<PathBuf>,
+        /// Write only the casted entity to this file instead of printing the result
+        #[arg(long, requires = "instance")]
+        write: Option<PathBuf>,
+        /// Exit successfully even when the cast is not fully compatible
+        #[arg(long, requires = "instance")]
+        allow_incompatible: bool,
     },
     /// Query entities using an expression
     Query {
@@ -88,6 +125,29 @@ enum Commands {
         #[arg(long)]
         gts_with_path: String,
     },
+    /// Print the IDs of the entities found under a path
+    Scan {
+        /// File or directory to scan (defaults to --path)
+        path: Option<String>,
+        /// Print file and entity counts instead of the IDs
+        #[arg(long)]
+        stats: bool,
+    },
+    /// Compare two schema files in both compatibility directions, failing if neither holds
+    Diff {
+        /// Old schema JSON file
+        old: PathBuf,
+        /// New schema JSON file
+        new: PathBuf,
+        /// Print the structured result as JSON instead of a report
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that every entity under a path has a valid GTS ID, failing if any does not
+    Check {
+        /// File or directory to check (defaults to --path)
+        path: Option<String>,
+    },
     /// List all entities
     List {
         #[arg(long, default_value = "100")]
@@ -99,6 +159,9 @@ enum Commands {
         host: String,
         #[arg(long, default_value = "8000")]
         port: u16,
+        /// Time the phases of inline casts and report the totals at /metrics
+        #[arg(long)]
+        cast_timings: bool,
     },
     /// Generate `OpenAPI` specification
     OpenapiSpec {
@@ -120,21 +183,18 @@ enum Commands {
     },
 }
 
+#[allow(clippy::too_many_lines)]
 pub async fn run() -> Result<()> {
     let cli = Cli::parse();
 
     // Set up logging to match Python implementation
-    // WARNING (no -v), INFO (-v), DEBUG (-vv)
-    let log_level = match cli.verbose {
+    // WARNING (no -v), INFO (-v), DEBUG (-vv), unless --log-level is given
+    let log_level = cli.log_level.unwrap_or(match cli.verbose {
         0 => tracing::Level::WARN,
         1 => tracing::Level::INFO,
         _ => tracing::Level::DEBUG,
-    };
-
-    tracing_subscriber::fmt()
-        .with_max_level(log_level)
-        .with_target(false)
-        .init();
+    });
+    init_logging(log_level, cli.log_format);
 
     // Parse path into Vec<String>
     let path = cli.path.map(|p| vec![p]);
@@ -143,12 +203,17 @@ pub async fn run() -> Result<()> {
     let mut ops = GtsOps::new(path, cli.config, cli.verbose as usize);
 
     match cli.command {
-        Commands::Server { host, port } => {
+        Commands::Server {
+            host,
+            port,
+            cast_timings,
+        } => {
             println!("starting the server @ http://{host}:{port}");
             if cli.verbose == 0 {
                 println!("use --verbose to see server logs");
             }
-            let server = GtsHttpServer::new(ops, host.clone(), port, cli.verbose);
+            let server = GtsHttpServer::new(ops, host.clone(), port, cli.verbose)
+                .with_cast_timings(cast_timings);
             server.run().await?;
         }
         Commands::OpenapiSpec { out, host, port } => {
@@ -161,9 +226,9 @@ pub async fn run() -> Result<()> {
             });
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
-        Commands::ValidateId { gts_id } => {
-            let result = ops.validate_id(&gts_id);
-            print_result(&result)?;
+        Commands::ValidateId { id, gts_id, json } => {
+            let id = id.or(gts_id).unwrap_or_default();
+            validate_id(&id, json)?;
         }
         Commands::ParseId { gts_id } => {
             let result = ops.parse_id(&gts_id);
@@ -195,6 +260,26 @@ pub async fn run() -> Result<()> {
         Commands::Cast {
             from_id,
             to_schema_id,
+            instance: Some(instance),
+            from_schema: Some(from_schema),
+            to_schema: Some(to_schema),
+            write,
+            allow_incompatible,
+        } => {
+            cast_files(
+                &from_id,
+                &to_schema_id,
+                &instance,
+                &from_schema,
+                &to_schema,
+                write.as_deref(),
+                allow_incompatible,
+            )?;
+        }
+        Commands::Cast {
+            from_id,
+            to_schema_id,
+            ..
         } => {
             let result = ops.cast(&from_id, &to_schema_id);
             print_result(&result)?;
@@ -207,6 +292,24 @@ pub async fn run() -> Result<()> {
             let result = ops.attr(&gts_with_path);
             print_result(&result)?;
         }
+        Commands::Scan {
+            path: scan_path,
+            stats,
+        } => {
+            let Some(paths) = scan_path.map(|p| vec![p]).or_else(|| ops.path.clone()) else {
+                bail!("No path to scan; pass it as an argument or use --path");
+            };
+            scan(&paths, ops.cfg.clone(), stats)?;
+        }
+        Commands::Check { path: check_path } => {
+            let Some(paths) = check_path.map(|p| vec![p]).or_else(|| ops.path.clone()) else {
+                bail!("No path to check; pass it as an argument or use --path");
+            };
+            check(&paths, ops.cfg.clone())?;
+        }
+        Commands::Diff { old, new, json } => {
+            diff_files(&old, &new, json)?;
+        }
         Commands::List { limit } => {
             let result = ops.get_entities(limit);
             print_result(&result)?;
@@ -219,6 +322,381 @@ pub async fn run() -> Result<()> {
     Ok(())
 }
 
+/// Parsed segment as printed by `validate-id`.
+#[derive(Serialize)]
+struct SegmentRow<'a> {
+    num: usize,
+    offset: usize,
+    vendor: &'a str,
+    package: &'a str,
+    namespace: &'a str,
+    type_name: &'a str,
+    ver_major: u32,
+    ver_minor: Option<u32>,
+    is_type: bool,
+    is_wildcard: bool,
+}
+
+impl<'a> From<&'a GtsIdSegment> for SegmentRow<'a> {
+    fn from(seg: &'a GtsIdSegment) -> Self {
+        Self {
+            num: seg.num,
+            offset: seg.offset,
+            vendor: &seg.vendor,
+            package: &seg.package,
+            namespace: &seg.namespace,
+            type_name: &seg.type_name,
+            ver_major: seg.ver_major,
+            ver_minor: seg.ver_minor,
+            is_type: seg.is_type,
+            is_wildcard: seg.is_wildcard,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ValidateIdOutput<'a> {
+    id: &'a str,
+    valid: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    segments: Vec<SegmentRow<'a>>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_details: Option<&'a GtsError>,
+}
+
+/// Parses `id` and prints its segments, failing if the ID is malformed.
+fn validate_id(id: &str, json: bool) -> Result<()> {
+    let gts_id = match GtsID::new(id) {
+        Ok(gts_id) => gts_id,
+        Err(e) => {
+            if json {
+                print_result(&ValidateIdOutput {
+                    id,
+                    valid: false,
+                    segments: Vec::new(),
+                    error: e.to_string(),
+                    error_details: Some(&e),
+                })?;
+            }
+            if let Some(context) = e.offset_context(id) {
+                bail!(context);
+            }
+            return Err(e.into());
+        }
+    };
+
+    let segments: Vec<SegmentRow> = gts_id.segments_iter().map(SegmentRow::from).collect();
+    if json {
+        return print_result(&ValidateIdOutput {
+            id: &gts_id.id,
+            valid: true,
+            segments,
+            error: String::new(),
+            error_details: None,
+        });
+    }
+
+    print_segments_table(&gts_id.id, &segments)
+}
+
+fn print_segments_table(id: &str, segments: &[SegmentRow]) -> Result<()> {
+    let header = [
+        "#",
+        "offset",
+        "vendor",
+        "package",
+        "namespace",
+        "type_name",
+        "major",
+        "minor",
+        "is_type",
+        "is_wildcard",
+    ];
+    let rows: Vec<Vec<String>> = segments
+        .iter()
+        .map(|seg| {
+            vec![
+                seg.num.to_string(),
+                seg.offset.to_string(),
+                seg.vendor.to_owned(),
+                seg.package.to_owned(),
+                seg.namespace.to_owned(),
+                seg.type_name.to_owned(),
+                seg.ver_major.to_string(),
+                seg.ver_minor.map(|m| m.to_string()).unwrap_or_default(),
+                seg.is_type.to_string(),
+                seg.is_wildcard.to_string(),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(std::iter::once(h.len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    writeln!(handle, "{id}: valid")?;
+    let cells = header.iter().map(|h| (*h).to_owned()).collect::<Vec<_>>();
+    for row in std::iter::once(&cells).chain(rows.iter()) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        writeln!(handle, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn read_json_file(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))
+}
+
+/// Casts an instance file between two schema files, failing unless the cast is fully
+/// compatible or `allow_incompatible` is set.
+fn cast_files(
+    from_id: &str,
+    to_schema_id: &str,
+    instance: &Path,
+    from_schema: &Path,
+    to_schema: &Path,
+    write: Option<&Path>,
+    allow_incompatible: bool,
+) -> Result<()> {
+    // Validate the casted entity so `is_fully_compatible` reflects the target schema
+    let options = CastOptions {
+        verify_bidirectional: true,
+        ..CastOptions::default()
+    };
+    let result = GtsEntityCastResult::cast_with_options(
+        from_id,
+        to_schema_id,
+        &read_json_file(instance)?,
+        &read_json_file(from_schema)?,
+        &read_json_file(to_schema)?,
+        None,
+        &options,
+    )?;
+
+    match write {
+        Some(out) => {
+            let Some(casted) = &result.casted_entity else {
+                bail!(
+                    "Cast produced no entity: {}",
+                    result.incompatibility_reasons.join("; ")
+                );
+            };
+            std::fs::write(out, serde_json::to_string_pretty(casted)? + "\n")?;
+        }
+        None => print_result(&result)?,
+    }
+
+    if !result.is_fully_compatible && !allow_incompatible {
+        bail!(
+            "Cast from {from_id} to {to_schema_id} is not fully compatible (use --allow-incompatible to ignore)"
+        );
+    }
+    Ok(())
+}
+
+/// Result of `diff`, comparing two schema files in both directions.
+#[derive(Serialize)]
+struct DiffOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    direction: Option<String>,
+    is_backward_compatible: bool,
+    is_forward_compatible: bool,
+    backward_errors: Vec<String>,
+    forward_errors: Vec<String>,
+    verdict: &'static str,
+}
+
+/// The schema's `$id` without the `gts://` prefix.
+fn schema_id(schema: &Value) -> Option<String> {
+    let id = schema.get("$id")?.as_str()?;
+    Some(id.strip_prefix("gts://").unwrap_or(id).to_owned())
+}
+
+/// Checks backward and forward compatibility between two schema files and reports
+/// both, failing if the schemas are compatible in neither direction.
+fn diff_files(old: &Path, new: &Path, json: bool) -> Result<()> {
+    let old_schema = read_json_file(old)?;
+    let new_schema = read_json_file(new)?;
+    let (is_backward_compatible, backward_errors) =
+        GtsEntityCastResult::check_backward_compatibility(&old_schema, &new_schema);
+    let (is_forward_compatible, forward_errors) =
+        GtsEntityCastResult::check_forward_compatibility(&old_schema, &new_schema);
+
+    let old_id = schema_id(&old_schema);
+    let new_id = schema_id(&new_schema);
+    let direction = match (&old_id, &new_id) {
+        (Some(from), Some(to)) => Some(CastDirection::from_ids(from, to).to_string()),
+        _ => None,
+    };
+    let verdict = match (is_backward_compatible, is_forward_compatible) {
+        (true, true) => "fully compatible",
+        (true, false) => "backward compatible only",
+        (false, true) => "forward compatible only",
+        (false, false) => "incompatible",
+    };
+    let output = DiffOutput {
+        old_id,
+        new_id,
+        direction,
+        is_backward_compatible,
+        is_forward_compatible,
+        backward_errors,
+        forward_errors,
+        verdict,
+    };
+
+    if json {
+        print_result(&output)?;
+    } else {
+        print_diff_report(old, new, &output)?;
+    }
+
+    if !is_backward_compatible && !is_forward_compatible {
+        bail!(
+            "{} and {} are neither backward nor forward compatible",
+            old.display(),
+            new.display()
+        );
+    }
+    Ok(())
+}
+
+fn print_diff_report(old: &Path, new: &Path, output: &DiffOutput) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    for (label, path, id) in [("old", old, &output.old_id), ("new", new, &output.new_id)] {
+        match id {
+            Some(id) => writeln!(handle, "{label}: {} ({id})", path.display())?,
+            None => writeln!(handle, "{label}: {}", path.display())?,
+        }
+    }
+    if let Some(direction) = &output.direction {
+        writeln!(handle, "direction: {direction}")?;
+    }
+    for (label, compatible, errors) in [
+        (
+            "backward",
+            output.is_backward_compatible,
+            &output.backward_errors,
+        ),
+        (
+            "forward",
+            output.is_forward_compatible,
+            &output.forward_errors,
+        ),
+    ] {
+        let status = if compatible {
+            "compatible"
+        } else {
+            "incompatible"
+        };
+        writeln!(handle, "{label}: {status}")?;
+        for error in errors {
+            writeln!(handle, "  - {error}")?;
+        }
+    }
+    writeln!(handle, "verdict: {}", output.verdict)?;
+    Ok(())
+}
+
+fn scan(paths: &[String], cfg: GtsConfig, stats: bool) -> Result<()> {
+    let mut reader = GtsFileReader::new(paths, Some(cfg));
+    let ids: Vec<String> = reader
+        .iter()
+        .filter_map(|entity| entity.gts_id.map(|id| id.id))
+        .collect();
+    if stats {
+        return print_result(&reader.stats());
+    }
+    for id in ids {
+        println!("{id}");
+    }
+    Ok(())
+}
+
+/// Invalid IDs found in one file by `check`.
+#[derive(Serialize)]
+struct CheckFileErrors<'a> {
+    path: &'a Path,
+    errors: Vec<CheckEntityError<'a>>,
+}
+
+#[derive(Serialize)]
+struct CheckEntityError<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    id: &'a str,
+    error: &'a str,
+}
+
+#[derive(Serialize)]
+struct CheckOutput<'a> {
+    files_scanned: usize,
+    entities: usize,
+    invalid_ids: usize,
+    files: Vec<CheckFileErrors<'a>>,
+}
+
+/// Reads every entity under `paths` and reports those whose ID field is not a valid
+/// GTS ID, grouped by file.
+fn check(paths: &[String], cfg: GtsConfig) -> Result<()> {
+    let mut reader = GtsFileReader::new(paths, Some(cfg));
+    let entities = reader.iter().count();
+    let invalid = reader.invalid_ids();
+
+    let mut by_file: BTreeMap<&Path, Vec<&GtsInvalidId>> = BTreeMap::new();
+    for entry in &invalid {
+        by_file.entry(&entry.path).or_default().push(entry);
+    }
+    print_result(&CheckOutput {
+        files_scanned: reader.stats().files_scanned,
+        entities,
+        invalid_ids: invalid.len(),
+        files: by_file
+            .into_iter()
+            .map(|(path, entries)| CheckFileErrors {
+                path,
+                errors: entries
+                    .into_iter()
+                    .map(|entry| CheckEntityError {
+                        index: entry.index,
+                        id: &entry.id,
+                        error: &entry.error,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    })?;
+
+    if !invalid.is_empty() {
+        bail!("Found {} entities with an invalid GTS ID", invalid.len());
+    }
+    Ok(())
+}
+
 fn print_result<T: serde::Serialize>(value: &T) -> Result<()> {
     let stdout = std::io::stdout();
     let mut handle = stdout.lock();
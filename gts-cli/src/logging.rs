@@ -1,6 +1,42 @@
 use axum::{body::Body, extract::Request, http::StatusCode, middleware::Next, response::Response};
 use chrono::Local;
 use std::time::Instant;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Format of the `tracing` log lines written to stderr.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Installs the global `tracing` subscriber writing to stderr at `level` and above.
+pub fn init_logging(level: Level, format: LogFormat) {
+    let subscriber = build_subscriber(level, format, std::io::stderr);
+    // A subscriber may already be installed (e.g. in tests); keep the existing one
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+fn build_subscriber<W>(
+    level: Level,
+    format: LogFormat,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => Box::new(builder.with_target(false).finish()),
+        LogFormat::Json => Box::new(builder.json().finish()),
+    }
+}
 
 // ANSI color codes
 struct Colors {
@@ -193,3 +229,53 @@ impl LoggingMiddleware {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("test").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_log_line_shape() {
+        let capture = Capture::default();
+        let subscriber = build_subscriber(Level::DEBUG, LogFormat::Json, capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(path = "a.json", "- discovered file");
+            tracing::trace!("filtered out");
+        });
+
+        let output = String::from_utf8(capture.0.lock().expect("test").clone()).expect("test");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let line: serde_json::Value = serde_json::from_str(lines[0]).expect("test");
+        assert_eq!(line["level"], "DEBUG");
+        assert_eq!(line["fields"]["message"], "- discovered file");
+        assert_eq!(line["fields"]["path"], "a.json");
+        assert!(line["timestamp"].is_string());
+        assert!(line["target"].is_string());
+    }
+}
@@ -1,11 +1,12 @@
 use axum::{
     extract::{Path, Query, State},
+    http::StatusCode,
     middleware,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-use gts::GtsOps;
+use gts::{CastOptions, CastTimings, GtsEntityCastResult, GtsOps, GtsWildcard, SchemaCastError};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::{Arc, Mutex};
@@ -15,6 +16,31 @@ use crate::logging::LoggingMiddleware;
 #[derive(Clone)]
 pub struct AppState {
     ops: Arc<Mutex<GtsOps>>,
+    /// Present when cast timings are enabled
+    cast_metrics: Option<Arc<Mutex<CastMetrics>>>,
+}
+
+/// Phase timings summed over the inline casts served so far.
+#[derive(Default)]
+struct CastMetrics {
+    casts: u64,
+    totals: CastTimings,
+}
+
+impl CastMetrics {
+    fn record(&mut self, timings: &CastTimings) {
+        self.casts = self.casts.saturating_add(1);
+        self.totals.flatten_us = self.totals.flatten_us.saturating_add(timings.flatten_us);
+        self.totals.compatibility_us = self
+            .totals
+            .compatibility_us
+            .saturating_add(timings.compatibility_us);
+        self.totals.cast_us = self.totals.cast_us.saturating_add(timings.cast_us);
+        self.totals.validation_us = self
+            .totals
+            .validation_us
+            .saturating_add(timings.validation_us);
+    }
 }
 
 pub struct GtsHttpServer {
@@ -22,6 +48,7 @@ pub struct GtsHttpServer {
     host: String,
     port: u16,
     verbose: u8,
+    cast_timings: bool,
 }
 
 impl GtsHttpServer {
@@ -31,13 +58,25 @@ impl GtsHttpServer {
             host,
             port,
             verbose,
+            cast_timings: false,
         }
     }
 
+    /// Time the phases of inline `/cast` requests, return them in each result and
+    /// aggregate them at `/metrics`.
+    #[must_use]
+    pub fn with_cast_timings(mut self, enabled: bool) -> Self {
+        self.cast_timings = enabled;
+        self
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         let verbose = self.verbose;
         let state = AppState {
             ops: Arc::new(Mutex::new(self.ops)),
+            cast_metrics: self
+                .cast_timings
+                .then(|| Arc::new(Mutex::new(CastMetrics::default()))),
         };
 
         let app = Self::create_router(state, verbose);
@@ -68,6 +107,7 @@ impl GtsHttpServer {
             .route("/cast", post(cast))
             .route("/query", get(query))
             .route("/attr", get(attr))
+            .route("/metrics", get(metrics))
             .with_state(state);
 
         // Add custom logging middleware if verbose >= 1
@@ -94,11 +134,19 @@ impl GtsHttpServer {
             }],
             "paths": {
                 "/entities": {
-                    "get": { "summary": "Get all entities in the registry" },
+                    "get": {
+                        "summary": "Get all entities in the registry, or the IDs matching a `pattern` wildcard"
+                    },
                     "post": { "summary": "Register a single entity" }
                 },
                 "/validate-id": {
                     "get": { "summary": "Validate GTS identifier" }
+                },
+                "/cast": {
+                    "post": { "summary": "Cast an instance to a target schema" }
+                },
+                "/metrics": {
+                    "get": { "summary": "Cast phase timings summed over inline casts, when enabled" }
                 }
             }
         })
@@ -136,9 +184,14 @@ struct AttrQuery {
 }
 
 #[derive(Deserialize)]
-struct LimitQuery {
+struct EntitiesQuery {
     #[serde(default = "default_limit")]
     limit: usize,
+    /// Wildcard such as `gts.x.core.*`; switches the response to the matching IDs,
+    /// of which at most `limit` are returned
+    pattern: Option<String>,
+    #[serde(default)]
+    include_content: bool,
 }
 
 #[derive(Deserialize)]
@@ -158,10 +211,26 @@ struct SchemaRegister {
     schema_content: Value,
 }
 
+/// Cast either a registered instance or an instance sent inline with its schemas.
+// Deserialized once per request, so the size of the inline variant does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Deserialize)]
-struct CastRequest {
-    instance_id: String,
+#[serde(untagged)]
+enum CastRequest {
+    Inline(InlineCastRequest),
+    Registered {
+        instance_id: String,
+        to_schema_id: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct InlineCastRequest {
+    from_id: String,
     to_schema_id: String,
+    instance: Value,
+    from_schema: Value,
+    to_schema: Value,
 }
 
 #[derive(Deserialize)]
@@ -172,11 +241,38 @@ struct ValidateInstanceRequest {
 // Async Handlers
 async fn get_entities(
     State(state): State<AppState>,
-    Query(params): Query<LimitQuery>,
-) -> impl IntoResponse {
+    Query(params): Query<EntitiesQuery>,
+) -> Response {
+    let Some(pattern) = params.pattern else {
+        let ops = state.ops.lock().unwrap();
+        return Json(ops.get_entities(params.limit)).into_response();
+    };
+    let wildcard = match GtsWildcard::new(&pattern) {
+        Ok(wildcard) => wildcard,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    };
+
     let ops = state.ops.lock().unwrap();
-    let result = ops.get_entities(params.limit);
-    Json(result)
+    let matches: Vec<Value> = ops
+        .entities_matching(&wildcard)
+        .into_iter()
+        .take(params.limit)
+        .filter_map(|entity| {
+            let id = entity.gts_id.as_ref()?.id.clone();
+            Some(if params.include_content {
+                json!({ "id": id, "content": entity.content })
+            } else {
+                Value::String(id)
+            })
+        })
+        .collect();
+    Json(matches).into_response()
 }
 
 async fn get_entity(
@@ -285,10 +381,58 @@ async fn compatibility(
     Json(result)
 }
 
-async fn cast(State(state): State<AppState>, Json(body): Json<CastRequest>) -> impl IntoResponse {
-    let mut ops = state.ops.lock().unwrap();
-    let result = ops.cast(&body.instance_id, &body.to_schema_id);
-    Json(result)
+async fn cast(State(state): State<AppState>, Json(body): Json<CastRequest>) -> Response {
+    match body {
+        CastRequest::Registered {
+            instance_id,
+            to_schema_id,
+        } => {
+            let mut ops = state.ops.lock().unwrap();
+            let result = ops.cast(&instance_id, &to_schema_id);
+            Json(result).into_response()
+        }
+        CastRequest::Inline(body) => {
+            let options = CastOptions {
+                collect_timings: state.cast_metrics.is_some(),
+                ..CastOptions::default()
+            };
+            match GtsEntityCastResult::cast_with_options(
+                &body.from_id,
+                &body.to_schema_id,
+                &body.instance,
+                &body.from_schema,
+                &body.to_schema,
+                None,
+                &options,
+            ) {
+                Ok(result) => {
+                    if let (Some(metrics), Some(timings)) = (&state.cast_metrics, &result.timings) {
+                        metrics.lock().unwrap().record(timings);
+                    }
+                    Json(result).into_response()
+                }
+                Err(e) => {
+                    let status = match e {
+                        SchemaCastError::InstanceMustBeObject => StatusCode::BAD_REQUEST,
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    (status, Json(json!({ "error": e.to_string() }))).into_response()
+                }
+            }
+        }
+    }
+}
+
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let Some(metrics) = &state.cast_metrics else {
+        return Json(json!({ "enabled": false }));
+    };
+    let metrics = metrics.lock().unwrap();
+    Json(json!({
+        "enabled": true,
+        "casts": metrics.casts,
+        "total_us": metrics.totals,
+    }))
 }
 
 async fn query(
@@ -305,3 +449,190 @@ async fn attr(State(state): State<AppState>, Query(params): Query<AttrQuery>) ->
     let result = ops.attr(&params.gts_with_path);
     Json(result)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    async fn spawn_server() -> SocketAddr {
+        spawn_server_with_metrics(None).await
+    }
+
+    async fn spawn_server_with_metrics(
+        cast_metrics: Option<Arc<Mutex<CastMetrics>>>,
+    ) -> SocketAddr {
+        let state = AppState {
+            ops: Arc::new(Mutex::new(GtsOps::new(None, None, 0))),
+            cast_metrics,
+        };
+        let app = GtsHttpServer::create_router(state, 0);
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("test");
+        let addr = listener.local_addr().expect("test");
+        tokio::spawn(async move { axum::serve(listener, app).await.expect("test") });
+        addr
+    }
+
+    async fn post_json(addr: SocketAddr, path: &str, body: &Value) -> (u16, Value) {
+        let body = body.to_string();
+        let request = format!(
+            "POST {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        send(addr, &request).await
+    }
+
+    async fn get_json(addr: SocketAddr, path_and_query: &str) -> (u16, Value) {
+        let request =
+            format!("GET {path_and_query} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+        send(addr, &request).await
+    }
+
+    async fn send(addr: SocketAddr, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).await.expect("test");
+        stream.write_all(request.as_bytes()).await.expect("test");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.expect("test");
+
+        let (head, body) = response.split_once("\r\n\r\n").expect("test");
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .expect("test")
+            .parse()
+            .expect("test");
+        (status, serde_json::from_str(body).expect("test"))
+    }
+
+    fn cast_body(instance: &Value) -> Value {
+        json!({
+            "from_id": "gts.x.test.cast.event.v1.0",
+            "to_schema_id": "gts.x.test.cast.event.v1.1~",
+            "instance": instance,
+            "from_schema": {
+                "$id": "gts.x.test.cast.event.v1.0~",
+                "type": "object",
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            },
+            "to_schema": {
+                "$id": "gts.x.test.cast.event.v1.1~",
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "priority": {"type": "string", "default": "normal"}
+                },
+                "required": ["name"]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cast_endpoint_minor_version_upgrade() {
+        let addr = spawn_server().await;
+
+        let (status, body) = post_json(addr, "/cast", &cast_body(&json!({"name": "a"}))).await;
+
+        assert_eq!(status, 200);
+        assert_eq!(body["direction"], "up");
+        assert_eq!(body["is_backward_compatible"], true);
+        assert_eq!(
+            body["casted_entity"],
+            json!({"name": "a", "priority": "normal"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cast_endpoint_reports_timings_when_enabled() {
+        let addr = spawn_server().await;
+        let (_, body) = post_json(addr, "/cast", &cast_body(&json!({"name": "a"}))).await;
+        assert!(body.get("timings").is_none());
+        let (status, body) = get_json(addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({"enabled": false}));
+
+        let metrics = Arc::new(Mutex::new(CastMetrics::default()));
+        let addr = spawn_server_with_metrics(Some(Arc::clone(&metrics))).await;
+        for _ in 0..2 {
+            let (status, body) = post_json(addr, "/cast", &cast_body(&json!({"name": "a"}))).await;
+            assert_eq!(status, 200);
+            for phase in ["flatten_us", "compatibility_us", "cast_us", "validation_us"] {
+                assert!(body["timings"][phase].is_u64(), "missing {phase}");
+            }
+        }
+
+        let (status, body) = get_json(addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["enabled"], true);
+        assert_eq!(body["casts"], 2);
+        assert!(body["total_us"]["cast_us"].is_u64());
+        assert_eq!(metrics.lock().expect("test").casts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cast_endpoint_rejects_non_object_instance() {
+        let addr = spawn_server().await;
+
+        let (status, body) = post_json(addr, "/cast", &cast_body(&json!("a"))).await;
+
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "Instance must be an object for casting");
+    }
+
+    #[tokio::test]
+    async fn test_entities_endpoint_filters_by_pattern() {
+        let addr = spawn_server().await;
+        for id in [
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.events.event.v1~x.app.orders.created.v1.0",
+            "gts.y.billing.invoices.invoice.v1~",
+        ] {
+            let (status, _) =
+                post_json(addr, "/entities", &json!({"$id": id, "type": "object"})).await;
+            assert_eq!(status, 200);
+        }
+
+        let (status, body) = get_json(addr, "/entities?pattern=gts.x.core.*").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            json!([
+                "gts.x.core.events.event.v1~",
+                "gts.x.core.events.event.v1~x.app.orders.created.v1.0"
+            ])
+        );
+
+        let (status, body) = get_json(addr, "/entities?pattern=gts.x.core.*&limit=1").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!(["gts.x.core.events.event.v1~"]));
+
+        let (status, body) = get_json(
+            addr,
+            "/entities?pattern=gts.y.billing.*&include_content=true",
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], "gts.y.billing.invoices.invoice.v1~");
+        assert_eq!(body[0]["content"]["type"], "object");
+
+        let (status, body) = get_json(addr, "/entities?pattern=gts.z.*").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_entities_endpoint_rejects_invalid_pattern() {
+        let addr = spawn_server().await;
+
+        let (status, body) = get_json(addr, "/entities?pattern=gts.x.*.events.*").await;
+
+        assert_eq!(status, 400);
+        assert!(body["error"]
+            .as_str()
+            .expect("test")
+            .starts_with("Invalid GTS wildcard pattern"));
+    }
+}
@@ -0,0 +1,77 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const INSTANCE_ID: &str = "gts.x.commerce.orders.order.v1.0~x.shop.orders.first.v1.0";
+
+fn fixture(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cast")
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn cast(to_version: &str, extra: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gts"))
+        .args([
+            "cast",
+            "--from-id",
+            INSTANCE_ID,
+            "--to-schema-id",
+            &format!("gts.x.commerce.orders.order.{to_version}~"),
+            "--instance",
+            &fixture("order.json"),
+            "--from-schema",
+            &fixture("order.v1.0.schema.json"),
+            "--to-schema",
+            &fixture(&format!("order.{to_version}.schema.json")),
+        ])
+        .args(extra)
+        .output()
+        .expect("run gts cast")
+}
+
+#[test]
+fn test_cast_writes_casted_entity() {
+    let out = std::env::temp_dir().join(format!("gts_cli_cast_{}.json", std::process::id()));
+    let output = cast("v1.1", &["--write", &out.to_string_lossy()]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let casted: Value =
+        serde_json::from_str(&fs::read_to_string(&out).expect("test")).expect("test");
+    assert_eq!(
+        casted,
+        json!({"id": INSTANCE_ID, "amount": 10, "currency": "USD"})
+    );
+    fs::remove_file(&out).expect("test");
+}
+
+#[test]
+fn test_cast_fails_when_not_fully_compatible() {
+    let output = cast("v1.2", &[]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not fully compatible (use --allow-incompatible to ignore)"));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["is_fully_compatible"], false);
+    assert_eq!(
+        report["backward_errors"],
+        json!(["Property 'amount' type changed from integer to string"])
+    );
+}
+
+#[test]
+fn test_cast_allow_incompatible_succeeds() {
+    let output = cast("v1.2", &["--allow-incompatible"]);
+
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["is_fully_compatible"], false);
+}
@@ -0,0 +1,61 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+fn fixture(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn test_check_reports_invalid_ids_grouped_by_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_gts"))
+        .args(["check", &fixture("check")])
+        .output()
+        .expect("run gts check");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Found 1 entities with an invalid GTS ID"));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["files_scanned"], 2);
+    assert_eq!(report["entities"], 3);
+    assert_eq!(report["invalid_ids"], 1);
+
+    let files = report["files"].as_array().expect("test");
+    assert_eq!(files.len(), 1);
+    assert!(files[0]["path"]
+        .as_str()
+        .expect("test")
+        .ends_with("events.json"));
+    let errors = files[0]["errors"].as_array().expect("test");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["index"], 1);
+    assert_eq!(
+        errors[0]["id"],
+        "gts.x.core.events.event.v1~x.app.Orders.updated.v1.0"
+    );
+    assert!(errors[0]["error"]
+        .as_str()
+        .expect("test")
+        .contains("Must be lower case"));
+}
+
+#[test]
+fn test_check_succeeds_when_all_ids_are_valid() {
+    let output = Command::new(env!("CARGO_BIN_EXE_gts"))
+        .args(["check", &fixture("check/event.schema.json")])
+        .output()
+        .expect("run gts check");
+
+    assert!(output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["invalid_ids"], 0);
+    assert!(report["files"].as_array().expect("test").is_empty());
+}
@@ -0,0 +1,60 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+fn fixture(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/diff")
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn test_diff_reports_breaking_change() {
+    let output = Command::new(env!("CARGO_BIN_EXE_gts"))
+        .args([
+            "diff",
+            "--json",
+            &fixture("order.v1.schema.json"),
+            &fixture("order.v1.1.schema.json"),
+        ])
+        .output()
+        .expect("run gts diff");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("neither backward nor forward compatible"));
+
+    let report: Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["old_id"], "gts.x.commerce.orders.order.v1~");
+    assert_eq!(report["new_id"], "gts.x.commerce.orders.order.v1.1~");
+    assert_eq!(report["direction"], "up");
+    assert_eq!(report["is_backward_compatible"], false);
+    assert_eq!(report["is_forward_compatible"], false);
+    assert_eq!(report["verdict"], "incompatible");
+    for errors in [&report["backward_errors"], &report["forward_errors"]] {
+        assert_eq!(
+            errors,
+            &serde_json::json!(["Property 'amount' type changed from integer to string"])
+        );
+    }
+}
+
+#[test]
+fn test_diff_prints_report_for_compatible_schemas() {
+    let schema = fixture("order.v1.schema.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_gts"))
+        .args(["diff", &schema, &schema])
+        .output()
+        .expect("run gts diff");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("direction: none"));
+    assert!(stdout.contains("backward: compatible"));
+    assert!(stdout.contains("forward: compatible"));
+    assert!(stdout.contains("verdict: fully compatible"));
+}
@@ -0,0 +1,114 @@
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinError};
+
+use crate::entities::{GtsConfig, GtsEntity};
+use crate::files_reader::GtsFileReader;
+use crate::store::GtsReader;
+
+/// Entities buffered between the blocking reader and the async consumer.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Reads GTS entities from files without blocking the tokio runtime.
+///
+/// Each read runs a fresh `GtsFileReader` on tokio's blocking thread pool, so the
+/// directory walk and file I/O happen off the async worker threads. Methods must be
+/// called from within a tokio runtime.
+#[derive(Debug, Clone)]
+pub struct AsyncGtsFileReader {
+    paths: Vec<String>,
+    cfg: Option<GtsConfig>,
+}
+
+impl AsyncGtsFileReader {
+    #[must_use]
+    pub fn new(paths: &[String], cfg: Option<GtsConfig>) -> Self {
+        AsyncGtsFileReader {
+            paths: paths.to_vec(),
+            cfg,
+        }
+    }
+
+    /// Sends the entities in the order `GtsFileReader::iter` yields them. The channel
+    /// closes once every entity has been sent.
+    ///
+    /// This does not stream from disk: `GtsFileReader::iter` parses every file before
+    /// the first entity is sent, so only the hand-off to the consumer is incremental.
+    /// Dropping the receiver stops the remaining sends.
+    #[must_use]
+    pub fn entities(&self) -> mpsc::Receiver<GtsEntity> {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let mut reader = GtsFileReader::new(&self.paths, self.cfg.clone());
+        task::spawn_blocking(move || {
+            for entity in reader.iter() {
+                if tx.blocking_send(entity).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
+    /// Reads every entity.
+    ///
+    /// # Errors
+    /// Returns `JoinError` if the blocking read panicked.
+    pub async fn read_all(&self) -> Result<Vec<GtsEntity>, JoinError> {
+        let mut reader = GtsFileReader::new(&self.paths, self.cfg.clone());
+        task::spawn_blocking(move || reader.iter().collect()).await
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_files;
+    use serde_json::json;
+    use std::fs;
+
+    fn sorted_ids(entities: Vec<GtsEntity>) -> Vec<String> {
+        let mut ids: Vec<String> = entities
+            .into_iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_async_reader_matches_sync_reader() {
+        let items: Vec<_> = (0..200)
+            .map(|i| json!({"id": format!("gts.x.core.events.event.v1~x.app.items.item_{i}.v1.0")}))
+            .collect();
+        let dir = write_files(
+            "async_reader",
+            &[
+                (
+                    "schema.json",
+                    json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+                ),
+                ("nested/items.json", json!(items)),
+            ],
+        );
+        let paths = vec![dir.to_string_lossy().to_string()];
+
+        let expected = sorted_ids(GtsFileReader::new(&paths, None).iter().collect());
+        assert_eq!(expected.len(), 201);
+
+        let reader = AsyncGtsFileReader::new(&paths, None);
+        let mut rx = reader.entities();
+        let mut streamed = Vec::new();
+        while let Some(entity) = rx.recv().await {
+            streamed.push(entity);
+        }
+        assert_eq!(sorted_ids(streamed), expected);
+        assert_eq!(sorted_ids(reader.read_all().await.expect("test")), expected);
+
+        // Dropping the receiver early ends the blocking read
+        let mut rx = reader.entities();
+        assert!(rx.recv().await.is_some());
+        drop(rx);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+}
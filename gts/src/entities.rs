@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use serde_json::{Number, Value};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
 
 use crate::gts::GtsID;
 use crate::path_resolver::JsonPathResolver;
-use crate::schema_cast::{GtsEntityCastResult, SchemaCastError};
+use crate::schema_cast::{GtsEntityCastResult, SchemaCastError, SchemaResolver};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
@@ -62,6 +63,36 @@ impl GtsFile {
 pub struct GtsConfig {
     pub entity_id_fields: Vec<String>,
     pub schema_id_fields: Vec<String>,
+    /// Directory names skipped by `GtsFileReader`, in addition to the defaults.
+    #[serde(default)]
+    pub exclude_dirs: Vec<String>,
+    /// Skip only `exclude_dirs` instead of adding them to the default exclude list.
+    #[serde(default)]
+    pub replace_default_excludes: bool,
+    /// When non-empty, `GtsFileReader` only reads files whose path relative to the
+    /// scanned directory matches one of these globs.
+    #[serde(default)]
+    pub include_globs: Vec<String>,
+    /// Field holding the entity ID. When set, it replaces `entity_id_fields` for ID lookup.
+    #[serde(default)]
+    pub id_field: Option<String>,
+    /// Fields tried in order after `id_field` when it is missing or empty.
+    #[serde(default)]
+    pub id_fallback_fields: Vec<String>,
+    /// Collect files that fail to parse so `GtsFileReader::parse_errors` can report them.
+    #[serde(default)]
+    pub fail_on_parse_error: bool,
+    /// File name suffixes read by `GtsFileReader`, matched case-insensitively. A leading
+    /// dot is optional, and a trailing `.gz` on the file name is ignored.
+    #[serde(default = "default_extensions")]
+    pub extensions: Vec<String>,
+}
+
+fn default_extensions() -> Vec<String> {
+    [".json", ".jsonc", ".gts", ".yaml", ".yml"]
+        .iter()
+        .map(|ext| (*ext).to_owned())
+        .collect()
 }
 
 impl Default for GtsConfig {
@@ -89,7 +120,159 @@ impl Default for GtsConfig {
                 "type".to_owned(),
                 "schema".to_owned(),
             ],
+            exclude_dirs: Vec::new(),
+            replace_default_excludes: false,
+            include_globs: Vec::new(),
+            id_field: None,
+            id_fallback_fields: Vec::new(),
+            fail_on_parse_error: false,
+            extensions: default_extensions(),
+        }
+    }
+}
+
+impl GtsConfig {
+    /// Starts from the default configuration.
+    #[must_use]
+    pub fn builder() -> GtsConfigBuilder {
+        GtsConfigBuilder::default()
+    }
+
+    /// Fields consulted, in order, when locating an entity's GTS ID: `id_field` followed
+    /// by `id_fallback_fields` if an override is set, otherwise `entity_id_fields`.
+    #[must_use]
+    pub fn id_lookup_fields(&self) -> Vec<String> {
+        match &self.id_field {
+            Some(field) => std::iter::once(field.clone())
+                .chain(self.id_fallback_fields.iter().cloned())
+                .collect(),
+            None => self.entity_id_fields.clone(),
+        }
+    }
+}
+
+/// Invalid option combinations rejected by `GtsConfigBuilder::build`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GtsConfigError {
+    #[error("At least one file extension is required")]
+    NoExtensions,
+    #[error("Invalid file extension '{0}'")]
+    InvalidExtension(String),
+    #[error("No entity ID fields configured")]
+    NoIdFields,
+    #[error("Empty field name in {0}")]
+    EmptyField(&'static str),
+}
+
+/// Builds a validated `GtsConfig`; unset options keep their defaults.
+#[derive(Debug, Clone, Default)]
+pub struct GtsConfigBuilder {
+    cfg: GtsConfig,
+}
+
+fn to_strings<S: Into<String>>(items: impl IntoIterator<Item = S>) -> Vec<String> {
+    items.into_iter().map(Into::into).collect()
+}
+
+impl GtsConfigBuilder {
+    #[must_use]
+    pub fn entity_id_fields<S: Into<String>>(
+        mut self,
+        fields: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.cfg.entity_id_fields = to_strings(fields);
+        self
+    }
+
+    #[must_use]
+    pub fn schema_id_fields<S: Into<String>>(
+        mut self,
+        fields: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.cfg.schema_id_fields = to_strings(fields);
+        self
+    }
+
+    #[must_use]
+    pub fn exclude_dirs<S: Into<String>>(mut self, dirs: impl IntoIterator<Item = S>) -> Self {
+        self.cfg.exclude_dirs = to_strings(dirs);
+        self
+    }
+
+    #[must_use]
+    pub fn replace_default_excludes(mut self, replace: bool) -> Self {
+        self.cfg.replace_default_excludes = replace;
+        self
+    }
+
+    #[must_use]
+    pub fn include_globs<S: Into<String>>(mut self, globs: impl IntoIterator<Item = S>) -> Self {
+        self.cfg.include_globs = to_strings(globs);
+        self
+    }
+
+    #[must_use]
+    pub fn id_field(mut self, field: impl Into<String>) -> Self {
+        self.cfg.id_field = Some(field.into());
+        self
+    }
+
+    #[must_use]
+    pub fn id_fallback_fields<S: Into<String>>(
+        mut self,
+        fields: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.cfg.id_fallback_fields = to_strings(fields);
+        self
+    }
+
+    #[must_use]
+    pub fn fail_on_parse_error(mut self, fail: bool) -> Self {
+        self.cfg.fail_on_parse_error = fail;
+        self
+    }
+
+    #[must_use]
+    pub fn extensions<S: Into<String>>(mut self, extensions: impl IntoIterator<Item = S>) -> Self {
+        self.cfg.extensions = to_strings(extensions);
+        self
+    }
+
+    /// Checks the options and returns the configuration.
+    ///
+    /// # Errors
+    /// Returns `GtsConfigError` if no extension or ID field is configured, if an
+    /// extension is blank, or if a field name is empty.
+    pub fn build(self) -> Result<GtsConfig, GtsConfigError> {
+        let cfg = self.cfg;
+        if cfg.extensions.is_empty() {
+            return Err(GtsConfigError::NoExtensions);
+        }
+        if let Some(ext) = cfg
+            .extensions
+            .iter()
+            .find(|ext| ext.trim().trim_start_matches('.').is_empty())
+        {
+            return Err(GtsConfigError::InvalidExtension(ext.clone()));
+        }
+        if cfg.id_lookup_fields().is_empty() {
+            return Err(GtsConfigError::NoIdFields);
+        }
+        if cfg.id_field.as_deref().is_some_and(str::is_empty) {
+            return Err(GtsConfigError::EmptyField("id_field"));
         }
+        let field_lists = [
+            ("entity_id_fields", &cfg.entity_id_fields),
+            ("schema_id_fields", &cfg.schema_id_fields),
+            ("id_fallback_fields", &cfg.id_fallback_fields),
+        ];
+        if let Some((name, _)) = field_lists
+            .iter()
+            .find(|(_, fields)| fields.iter().any(String::is_empty))
+        {
+            return Err(GtsConfigError::EmptyField(name));
+        }
+        Ok(cfg)
     }
 }
 
@@ -242,6 +425,17 @@ impl GtsEntity {
         JsonPathResolver::new(gts_id, self.content.clone()).resolve(path)
     }
 
+    /// Hash of the entity's content for change detection.
+    ///
+    /// Object keys are hashed in sorted order and numbers by value, so `1`, `1.0`
+    /// and `1e0` hash equally whatever the key order. The hash is 64-bit FNV-1a over
+    /// a fixed encoding, so it can be stored and compared across runs, platforms and
+    /// Rust releases.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        content_hash(&self.content)
+    }
+
     /// Casts this entity to a different schema.
     ///
     /// # Errors
@@ -250,7 +444,7 @@ impl GtsEntity {
         &self,
         to_schema: &GtsEntity,
         from_schema: &GtsEntity,
-        resolver: Option<&()>,
+        resolver: Option<&dyn SchemaResolver>,
     ) -> Result<GtsEntityCastResult, SchemaCastError> {
         if self.is_schema {
             // When casting a schema, from_schema might be a standard JSON Schema (no gts_id)
@@ -431,7 +625,7 @@ impl GtsEntity {
     }
 
     fn calc_json_entity_id(&mut self, cfg: &GtsConfig) -> Option<String> {
-        if let Some(id) = self.first_non_empty_field(&cfg.entity_id_fields) {
+        if let Some(id) = self.first_non_empty_field(&cfg.id_lookup_fields()) {
             return Some(id);
         }
 
@@ -455,7 +649,7 @@ impl GtsEntity {
         }
 
         // Fallback to entity ID logic
-        let idv = self.first_non_empty_field(&cfg.entity_id_fields);
+        let idv = self.first_non_empty_field(&cfg.id_lookup_fields());
         if let Some(ref id) = idv {
             if GtsID::is_valid(id) {
                 if id.ends_with('~') {
@@ -481,6 +675,147 @@ impl GtsEntity {
     }
 }
 
+/// Differences between two entity sets, keyed by GTS ID and sorted by it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityDiff {
+    pub added: Vec<GtsID>,
+    pub removed: Vec<GtsID>,
+    /// IDs present in both sets with different content, with the old and new
+    /// content hashes
+    pub changed: Vec<(GtsID, u64, u64)>,
+}
+
+impl EntityDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two snapshots of entities by GTS ID.
+///
+/// Entities without a GTS ID are ignored and, when an ID occurs more than once in a
+/// set, its first entity is used. Content is compared by `GtsEntity::content_hash`,
+/// so neither key order nor number formatting such as `1` versus `1.0` counts as a
+/// change.
+#[must_use]
+pub fn diff_entities(old: &[GtsEntity], new: &[GtsEntity]) -> EntityDiff {
+    fn by_id(entities: &[GtsEntity]) -> BTreeMap<&str, &GtsEntity> {
+        let mut map = BTreeMap::new();
+        for entity in entities {
+            if let Some(gts_id) = &entity.gts_id {
+                map.entry(gts_id.id.as_str()).or_insert(entity);
+            }
+        }
+        map
+    }
+
+    let old_by_id = by_id(old);
+    let new_by_id = by_id(new);
+    let mut diff = EntityDiff::default();
+
+    for (id, new_entity) in &new_by_id {
+        let Some(gts_id) = new_entity.gts_id.clone() else {
+            continue;
+        };
+        let Some(old_entity) = old_by_id.get(id) else {
+            diff.added.push(gts_id);
+            continue;
+        };
+        let (old_hash, new_hash) = (old_entity.content_hash(), new_entity.content_hash());
+        if old_hash != new_hash {
+            diff.changed.push((gts_id, old_hash, new_hash));
+        }
+    }
+    diff.removed = old_by_id
+        .iter()
+        .filter(|(id, _)| !new_by_id.contains_key(*id))
+        .filter_map(|(_, entity)| entity.gts_id.clone())
+        .collect();
+    diff
+}
+
+/// 64-bit FNV-1a, fixed by its specification unlike `DefaultHasher`.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes a length as 8 little-endian bytes whatever the platform's `usize`.
+    fn write_len(&mut self, len: usize) {
+        self.write(&u64::try_from(len).unwrap_or(u64::MAX).to_le_bytes());
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        self.write(s.as_bytes());
+    }
+}
+
+/// Decimal text of `n` that is equal for equal values: integral floats print as
+/// integers and `-0` as `0`.
+fn canonical_number(n: &Number) -> String {
+    if let Some(i) = n.as_i64() {
+        return i.to_string();
+    }
+    if let Some(u) = n.as_u64() {
+        return u.to_string();
+    }
+    match n.as_f64() {
+        Some(0.0) => "0".to_owned(),
+        Some(f) => f.to_string(),
+        None => n.to_string(),
+    }
+}
+
+/// Hashes `value` with object keys in sorted order, so equal values hash equally
+/// whatever their key order. See `GtsEntity::content_hash`.
+fn content_hash(value: &Value) -> u64 {
+    fn hash_value(value: &Value, hasher: &mut Fnv1a) {
+        match value {
+            Value::Null => hasher.write(&[0]),
+            Value::Bool(b) => hasher.write(&[1, u8::from(*b)]),
+            Value::Number(n) => {
+                hasher.write(&[2]);
+                hasher.write_str(&canonical_number(n));
+            }
+            Value::String(s) => {
+                hasher.write(&[3]);
+                hasher.write_str(s);
+            }
+            Value::Array(items) => {
+                hasher.write(&[4]);
+                hasher.write_len(items.len());
+                for item in items {
+                    hash_value(item, hasher);
+                }
+            }
+            Value::Object(map) => {
+                hasher.write(&[5]);
+                hasher.write_len(map.len());
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+                for (key, item) in entries {
+                    hasher.write_str(key);
+                    hash_value(item, hasher);
+                }
+            }
+        }
+    }
+
+    let mut hasher = Fnv1a(Fnv1a::OFFSET_BASIS);
+    hash_value(value, &mut hasher);
+    hasher.0
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -740,6 +1075,96 @@ mod tests {
         assert!(cfg.entity_id_fields.contains(&"gtsId".to_owned()));
     }
 
+    #[test]
+    fn test_gts_config_custom_id_field() {
+        let cfg = GtsConfig {
+            id_field: Some("schema_id".to_owned()),
+            ..GtsConfig::default()
+        };
+        let content = json!({
+            "id": "gts.vendor.package.namespace.other.v1.0",
+            "schema_id": "gts.vendor.package.namespace.type.v1.0"
+        });
+
+        let entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        assert_eq!(
+            entity.gts_id.as_ref().map(|id| id.id.as_str()),
+            Some("gts.vendor.package.namespace.type.v1.0")
+        );
+        assert_eq!(entity.selected_entity_field.as_deref(), Some("schema_id"));
+
+        // Without the override the default fields are used
+        let entity = GtsEntity::new(
+            None,
+            None,
+            &content,
+            Some(&GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        assert_eq!(entity.selected_entity_field.as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn test_gts_config_id_fallback_fields_order() {
+        let cfg = GtsConfig {
+            id_field: Some("schema_id".to_owned()),
+            id_fallback_fields: vec!["ref_id".to_owned(), "legacy_id".to_owned()],
+            ..GtsConfig::default()
+        };
+        assert_eq!(
+            cfg.id_lookup_fields(),
+            vec!["schema_id", "ref_id", "legacy_id"]
+        );
+
+        let entity_for = |content: &Value| {
+            GtsEntity::new(
+                None,
+                None,
+                content,
+                Some(&cfg),
+                None,
+                false,
+                String::new(),
+                None,
+                None,
+            )
+        };
+
+        let entity = entity_for(&json!({
+            "legacy_id": "gts.vendor.package.namespace.legacy.v1.0",
+            "ref_id": "gts.vendor.package.namespace.ref.v1.0"
+        }));
+        assert_eq!(entity.selected_entity_field.as_deref(), Some("ref_id"));
+        assert_eq!(
+            entity.gts_id.map(|id| id.id),
+            Some("gts.vendor.package.namespace.ref.v1.0".to_owned())
+        );
+
+        let entity = entity_for(&json!({
+            "schema_id": "",
+            "legacy_id": "gts.vendor.package.namespace.legacy.v1.0"
+        }));
+        assert_eq!(entity.selected_entity_field.as_deref(), Some("legacy_id"));
+
+        // Default fields are not consulted once an override is configured
+        let entity = entity_for(&json!({"id": "gts.vendor.package.namespace.type.v1.0"}));
+        assert!(entity.gts_id.is_none());
+    }
+
     #[test]
     fn test_gts_config_schema_id_fields() {
         let cfg = GtsConfig::default();
@@ -823,4 +1248,144 @@ mod tests {
         // When entity ID itself is a schema, selected_schema_id_field should be set to $schema
         assert_eq!(entity.selected_schema_id_field, Some("$schema".to_owned()));
     }
+
+    #[test]
+    fn test_gts_entity_content_hash() {
+        let entity = |content: Value| {
+            GtsEntity::new(
+                None,
+                None,
+                &content,
+                None,
+                None,
+                false,
+                String::new(),
+                None,
+                None,
+            )
+        };
+        let hash = |content: Value| entity(content).content_hash();
+
+        let original = hash(json!({
+            "id": "gts.x.core.events.event.v1.0",
+            "payload": {"a": 1, "b": [true, null, "x"]},
+            "count": 2
+        }));
+        let reordered = hash(json!({
+            "count": 2,
+            "payload": {"b": [true, null, "x"], "a": 1},
+            "id": "gts.x.core.events.event.v1.0"
+        }));
+        assert_eq!(original, reordered);
+
+        // Numbers hash by value
+        assert_eq!(hash(json!({"n": 1})), hash(json!({"n": 1.0})));
+        assert_eq!(hash(json!({"n": 0})), hash(json!({"n": -0.0})));
+        assert_eq!(
+            hash(serde_json::from_str(r#"{"n": 1e2}"#).expect("test")),
+            hash(json!({"n": 100}))
+        );
+
+        let id = "gts.x.core.events.event.v1.0";
+        for different in [
+            json!({"id": id, "payload": {"a": 2, "b": [true, null, "x"]}, "count": 2}),
+            json!({"id": id, "payload": {"a": 1, "b": [null, true, "x"]}, "count": 2}),
+            json!({"id": id, "payload": {"a": 1, "b": [true, null, "x"]}}),
+        ] {
+            assert_ne!(hash(different), original);
+        }
+        assert_ne!(hash(json!({"n": 1})), hash(json!({"n": "1"})));
+        assert_ne!(hash(json!({"n": 1})), hash(json!({"n": 1.5})));
+        assert_ne!(hash(json!({"ab": "c"})), hash(json!({"a": "bc"})));
+        assert_ne!(hash(json!(["a"])), hash(json!("a")));
+
+        // The hash is fixed across runs and releases
+        assert_eq!(hash(json!({"a": 1})), 13_424_608_012_461_563_529);
+    }
+
+    #[test]
+    fn test_diff_entities() {
+        let cfg = GtsConfig::default();
+        let entities = |values: &[Value]| -> Vec<GtsEntity> {
+            values
+                .iter()
+                .map(|content| {
+                    GtsEntity::new(
+                        None,
+                        None,
+                        content,
+                        Some(&cfg),
+                        None,
+                        false,
+                        String::new(),
+                        None,
+                        None,
+                    )
+                })
+                .collect()
+        };
+        let old = entities(&[
+            json!({"id": "gts.x.core.events.kept.v1.0", "a": 1, "b": 2}),
+            json!({"id": "gts.x.core.events.changed.v1.0", "a": 1}),
+            json!({"id": "gts.x.core.events.removed.v1.0"}),
+            json!({"name": "no id"}),
+        ]);
+        let new = entities(&[
+            json!({"id": "gts.x.core.events.added.v1.0"}),
+            json!({"b": 2, "a": 1, "id": "gts.x.core.events.kept.v1.0"}),
+            json!({"id": "gts.x.core.events.changed.v1.0", "a": 2}),
+        ]);
+
+        let diff = diff_entities(&old, &new);
+        let ids = |ids: &[GtsID]| -> Vec<String> { ids.iter().map(|id| id.id.clone()).collect() };
+        assert_eq!(ids(&diff.added), vec!["gts.x.core.events.added.v1.0"]);
+        assert_eq!(ids(&diff.removed), vec!["gts.x.core.events.removed.v1.0"]);
+        assert_eq!(diff.changed.len(), 1);
+        let (changed_id, old_hash, new_hash) = &diff.changed[0];
+        assert_eq!(changed_id.id, "gts.x.core.events.changed.v1.0");
+        assert_ne!(old_hash, new_hash);
+        assert_eq!(*old_hash, old[1].content_hash());
+
+        // Reordered keys hash equally
+        assert_eq!(old[0].content_hash(), new[1].content_hash());
+
+        assert!(diff_entities(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_gts_config_builder_validation() {
+        let cfg = GtsConfig::builder()
+            .id_field("key")
+            .id_fallback_fields(["name"])
+            .exclude_dirs(vec!["vendor".to_owned()])
+            .fail_on_parse_error(true)
+            .build()
+            .expect("test");
+        assert_eq!(cfg.id_lookup_fields(), vec!["key", "name"]);
+        assert_eq!(cfg.exclude_dirs, vec!["vendor"]);
+        assert!(cfg.fail_on_parse_error);
+        assert_eq!(cfg.extensions, GtsConfig::default().extensions);
+
+        let err = |builder: GtsConfigBuilder| builder.build().unwrap_err();
+        assert_eq!(
+            err(GtsConfig::builder().extensions(Vec::<String>::new())),
+            GtsConfigError::NoExtensions
+        );
+        assert_eq!(
+            err(GtsConfig::builder().extensions([".json", " . "])),
+            GtsConfigError::InvalidExtension(" . ".to_owned())
+        );
+        assert_eq!(
+            err(GtsConfig::builder().entity_id_fields(Vec::<String>::new())),
+            GtsConfigError::NoIdFields
+        );
+        assert_eq!(
+            err(GtsConfig::builder().schema_id_fields(["$schema", ""])),
+            GtsConfigError::EmptyField("schema_id_fields")
+        );
+        assert_eq!(
+            err(GtsConfig::builder().id_field("")),
+            GtsConfigError::EmptyField("id_field")
+        );
+    }
 }
@@ -1,19 +1,99 @@
+use flate2::read::GzDecoder;
+use serde::Serialize;
 use serde_json::Value;
+use std::cell::{OnceCell, RefCell};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
 use crate::entities::{GtsConfig, GtsEntity, GtsFile};
+use crate::gts::GtsID;
+use crate::schema_cast::{GtsEntityCastResult, SchemaResolver};
 use crate::store::GtsReader;
 
 const EXCLUDE_LIST: &[&str] = &["node_modules", "dist", "build"];
-const VALID_EXTENSIONS: &[&str] = &[".json", ".jsonc", ".gts", ".yaml", ".yml"];
+
+/// Source file of an entity and its position when the file holds an array.
+type EntityLocation = (PathBuf, Option<usize>);
 
 pub struct GtsFileReader {
     paths: Vec<PathBuf>,
     cfg: GtsConfig,
+    // `cfg.extensions`, lowercased and with a leading dot
+    extensions: Vec<String>,
     files: Vec<PathBuf>,
     initialized: bool,
+    // GTS ID -> source location, built on first use
+    index: OnceCell<HashMap<String, EntityLocation>>,
+    // GTS ID -> raw schema content, built on first use
+    schemas: OnceCell<HashMap<String, Value>>,
+    // GTS ID -> schema with cross-file $refs inlined
+    resolved_schemas: RefCell<HashMap<String, Value>>,
+    // Files that failed to parse, collected when `fail_on_parse_error` is set
+    parse_errors: RefCell<Vec<(PathBuf, String)>>,
+    // Entities skipped or re-identified because their ID field is malformed
+    invalid_ids: RefCell<Vec<GtsInvalidId>>,
+    // Entities of each parsed file, reused by `iter` while the file's mtime is unchanged
+    file_cache: RefCell<HashMap<PathBuf, CachedFile>>,
+    // Counts from the last `iter`
+    stats: GtsReadStats,
+}
+
+/// What parsing a file produced, as of its modification time.
+struct CachedFile {
+    modified: SystemTime,
+    entities: Vec<GtsEntity>,
+    invalid_ids: Vec<GtsInvalidId>,
+}
+
+/// Census of the files and entities seen by the last `GtsFileReader::iter`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GtsReadStats {
+    /// Files with a supported extension found under the configured paths
+    pub files_scanned: usize,
+    pub files_parsed: usize,
+    /// Parsed files whose entities were reused from an earlier `iter` because their
+    /// modification time is unchanged
+    pub files_reused: usize,
+    /// Files skipped because they could not be parsed
+    pub files_failed: usize,
+    /// Entities with a valid GTS ID
+    pub entities: usize,
+    /// Distinct type-level IDs (ending in `~`)
+    pub distinct_types: usize,
+    /// Entities with an instance ID (not ending in `~`)
+    pub instances: usize,
+}
+
+/// An entity whose ID field holds a value that is not a valid GTS ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GtsInvalidId {
+    pub path: PathBuf,
+    /// Position of the entity when the file holds an array
+    pub index: Option<usize>,
+    /// Raw value of the ID field
+    pub id: String,
+    /// Why `GtsID::new` rejected the value
+    pub error: String,
+}
+
+impl GtsReadStats {
+    fn count_entities(&mut self, entities: &[GtsEntity]) {
+        let mut types = HashSet::new();
+        for gts_id in entities.iter().filter_map(|e| e.gts_id.as_ref()) {
+            self.entities += 1;
+            if gts_id.is_type() {
+                types.insert(gts_id.id.as_str());
+            } else {
+                self.instances += 1;
+            }
+        }
+        self.distinct_types = types.len();
+    }
 }
 
 impl GtsFileReader {
@@ -24,16 +104,70 @@ impl GtsFileReader {
             .map(|p| PathBuf::from(shellexpand::tilde(p).to_string()))
             .collect();
 
+        let cfg = cfg.unwrap_or_default();
+        let extensions = cfg
+            .extensions
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| format!(".{ext}"))
+            .collect();
+
         GtsFileReader {
             paths,
-            cfg: cfg.unwrap_or_default(),
+            cfg,
+            extensions,
             files: Vec::new(),
             initialized: false,
+            index: OnceCell::new(),
+            schemas: OnceCell::new(),
+            resolved_schemas: RefCell::new(HashMap::new()),
+            parse_errors: RefCell::new(Vec::new()),
+            invalid_ids: RefCell::new(Vec::new()),
+            file_cache: RefCell::new(HashMap::new()),
+            stats: GtsReadStats::default(),
         }
     }
 
-    #[allow(clippy::cognitive_complexity)]
+    /// File and entity counts from the last `iter`; all zero before the first one.
+    #[must_use]
+    pub fn stats(&self) -> GtsReadStats {
+        self.stats.clone()
+    }
+
+    /// Files that failed to parse during the last `iter` or `count`, with the parser error.
+    ///
+    /// Only collected when `GtsConfig::fail_on_parse_error` is set; otherwise
+    /// failures are just logged as warnings.
+    #[must_use]
+    pub fn parse_errors(&self) -> Vec<(PathBuf, String)> {
+        self.parse_errors.borrow().clone()
+    }
+
+    /// Entities seen during the last `iter` or `count` whose ID field is not a valid GTS ID.
+    ///
+    /// Such entities are skipped, or identified by their schema ID when it is valid.
+    #[must_use]
+    pub fn invalid_ids(&self) -> Vec<GtsInvalidId> {
+        self.invalid_ids.borrow().clone()
+    }
+
     fn collect_files(&mut self) {
+        self.files = self.discover_files();
+    }
+
+    /// Discovers files on first use and clears the errors collected by the previous pass.
+    fn start_pass(&mut self) {
+        if !self.initialized {
+            self.collect_files();
+            self.initialized = true;
+        }
+        self.parse_errors.get_mut().clear();
+        self.invalid_ids.get_mut().clear();
+    }
+
+    #[allow(clippy::cognitive_complexity)]
+    fn discover_files(&self) -> Vec<PathBuf> {
         let mut seen = std::collections::HashSet::new();
         let mut collected = Vec::new();
 
@@ -41,67 +175,207 @@ impl GtsFileReader {
             let resolved_path = path.canonicalize().unwrap_or_else(|_| path.clone());
 
             if resolved_path.is_file() {
-                if let Some(ext) = resolved_path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
-                        let rp = resolved_path.to_string_lossy().to_string();
-                        if !seen.contains(&rp) {
-                            seen.insert(rp.clone());
-                            tracing::debug!("- discovered file: {:?}", resolved_path);
-                            collected.push(resolved_path.clone());
-                        }
+                if self.has_valid_extension(&resolved_path) {
+                    let rp = resolved_path.to_string_lossy().to_string();
+                    if !seen.contains(&rp) {
+                        seen.insert(rp.clone());
+                        tracing::debug!("- discovered file: {:?}", resolved_path);
+                        collected.push(resolved_path.clone());
                     }
                 }
             } else if resolved_path.is_dir() {
+                // Pruning excluded dirs here also keeps symlinks to them from being followed;
+                // other symlink loops are reported by walkdir as errors and skipped
                 for entry in WalkDir::new(&resolved_path)
                     .follow_links(true)
                     .into_iter()
+                    .filter_entry(|e| {
+                        e.depth() == 0
+                            || !e.file_type().is_dir()
+                            || !self.is_excluded_dir(&e.file_name().to_string_lossy())
+                    })
                     .flatten()
                 {
                     let path = entry.path();
 
-                    // Skip excluded directories
-                    if path.is_dir() {
-                        if let Some(name) = path.file_name() {
-                            if EXCLUDE_LIST.contains(&name.to_string_lossy().as_ref()) {
-                                continue;
-                            }
+                    if path.is_file()
+                        && self.is_included(&resolved_path, path)
+                        && self.has_valid_extension(path)
+                    {
+                        let rp = path
+                            .canonicalize()
+                            .unwrap_or_else(|_| path.to_path_buf())
+                            .to_string_lossy()
+                            .to_string();
+                        if !seen.contains(&rp) {
+                            seen.insert(rp.clone());
+                            tracing::debug!("- discovered file: {:?}", path);
+                            collected.push(PathBuf::from(rp));
                         }
                     }
+                }
+            }
+        }
 
-                    if path.is_file() {
-                        if let Some(ext) = path.extension() {
-                            let ext_str = ext.to_string_lossy().to_lowercase();
-                            if VALID_EXTENSIONS.contains(&format!(".{ext_str}").as_str()) {
-                                let rp = path
-                                    .canonicalize()
-                                    .unwrap_or_else(|_| path.to_path_buf())
-                                    .to_string_lossy()
-                                    .to_string();
-                                if !seen.contains(&rp) {
-                                    seen.insert(rp.clone());
-                                    tracing::debug!("- discovered file: {:?}", path);
-                                    collected.push(PathBuf::from(rp));
-                                }
-                            }
-                        }
+        collected
+    }
+
+    fn is_excluded_dir(&self, name: &str) -> bool {
+        self.cfg.exclude_dirs.iter().any(|d| d == name)
+            || (!self.cfg.replace_default_excludes && EXCLUDE_LIST.contains(&name))
+    }
+
+    /// Whether a file found under `root` matches the configured include globs.
+    fn is_included(&self, root: &Path, path: &Path) -> bool {
+        if self.cfg.include_globs.is_empty() {
+            return true;
+        }
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.cfg
+            .include_globs
+            .iter()
+            .any(|glob| glob_match(glob, &relative))
+    }
+
+    /// Returns the schema with the given GTS ID, with every `$ref` to another
+    /// schema in the file set inlined.
+    ///
+    /// The schema index is built on first use and resolved schemas are cached
+    /// until `reset` is called. References that cannot be resolved are left in
+    /// place and logged as warnings.
+    #[must_use]
+    pub fn resolve_schema(&self, schema_id: &str) -> Option<Value> {
+        let schema_id = Self::normalize_ref(schema_id);
+        if let Some(cached) = self.resolved_schemas.borrow().get(schema_id) {
+            return Some(cached.clone());
+        }
+
+        let raw = self.schema_index().get(schema_id)?;
+        let resolved = match GtsEntityCastResult::resolve_refs(raw, self) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                tracing::warn!("Failed to resolve $refs in schema {}: {}", schema_id, e);
+                raw.clone()
+            }
+        };
+
+        self.resolved_schemas
+            .borrow_mut()
+            .insert(schema_id.to_owned(), resolved.clone());
+        Some(resolved)
+    }
+
+    fn schema_index(&self) -> &HashMap<String, Value> {
+        self.schemas.get_or_init(|| {
+            let mut index = HashMap::new();
+            for file_path in &self.known_files() {
+                for entity in self.process_file(file_path) {
+                    if !entity.is_schema {
+                        continue;
+                    }
+                    if let Some(gts_id) = entity.gts_id {
+                        index.entry(gts_id.id).or_insert(entity.content);
                     }
                 }
             }
+            tracing::debug!("Indexed {} schemas from {:?}", index.len(), self.paths);
+            index
+        })
+    }
+
+    fn entity_index(&self) -> &HashMap<String, EntityLocation> {
+        self.index.get_or_init(|| {
+            let entities: Vec<GtsEntity> = self
+                .known_files()
+                .iter()
+                .flat_map(|file_path| self.process_file(file_path))
+                .collect();
+            Self::build_index(&entities)
+        })
+    }
+
+    /// Maps each entity's GTS ID to its source location; on duplicates the first
+    /// discovered entity wins.
+    fn build_index(entities: &[GtsEntity]) -> HashMap<String, EntityLocation> {
+        let mut index = HashMap::new();
+        for entity in entities {
+            let (Some(gts_id), Some(file)) = (&entity.gts_id, &entity.file) else {
+                continue;
+            };
+            let location = (PathBuf::from(&file.path), entity.list_sequence);
+            match index.entry(gts_id.id.clone()) {
+                Entry::Occupied(existing) => {
+                    tracing::warn!(
+                        "Duplicate GTS ID {} in {:?}, keeping the one from {:?}",
+                        gts_id.id,
+                        location,
+                        existing.get()
+                    );
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(location);
+                }
+            }
+        }
+        index
+    }
+
+    fn known_files(&self) -> Vec<PathBuf> {
+        if self.initialized {
+            self.files.clone()
+        } else {
+            self.discover_files()
         }
+    }
+
+    fn normalize_ref(uri: &str) -> &str {
+        uri.strip_prefix("gts://").unwrap_or(uri)
+    }
+
+    /// Whether the file name, ignoring a trailing `.gz`, ends with one of the configured
+    /// extensions. Multi-part extensions such as `.schema.json` are supported.
+    fn has_valid_extension(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+        let name = name.to_string_lossy().to_lowercase();
+        let name = name.strip_suffix(".gz").unwrap_or(&name);
+        self.extensions
+            .iter()
+            .any(|ext| name.ends_with(ext.as_str()))
+    }
 
-        self.files = collected;
+    /// Returns the lowercased extension that determines how a file is parsed, and whether
+    /// the file is gzip-compressed. For `entity.json.gz` this is `("json", true)`.
+    fn file_extension(path: &Path) -> Option<(String, bool)> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        if ext != "gz" {
+            return Some((ext, false));
+        }
+        let inner = Path::new(path.file_stem()?)
+            .extension()?
+            .to_string_lossy()
+            .to_lowercase();
+        Some((inner, true))
     }
 
     fn load_json_file(file_path: &Path) -> Result<Value, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(file_path)?;
+        // Determine file type by extension, looking through a trailing `.gz`
+        let (extension, compressed) = Self::file_extension(file_path).unwrap_or_default();
 
-        // Determine file type by extension
-        let extension = file_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(str::to_lowercase)
-            .unwrap_or_default();
+        let content = if compressed {
+            let mut content = String::new();
+            GzDecoder::new(fs::File::open(file_path)?).read_to_string(&mut content)?;
+            content
+        } else {
+            fs::read_to_string(file_path)?
+        };
 
         let value: Value = match extension.as_str() {
             "yaml" | "yml" => {
@@ -118,36 +392,116 @@ impl GtsFileReader {
         Ok(value)
     }
 
-    #[allow(clippy::cognitive_complexity)]
+    pub(crate) fn json_file(file_path: &Path, content: &Value) -> GtsFile {
+        GtsFile::new(
+            file_path.to_string_lossy().to_string(),
+            file_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            content.clone(),
+        )
+    }
+
+    fn make_entity(
+        &self,
+        json_file: &GtsFile,
+        list_sequence: Option<usize>,
+        content: &Value,
+    ) -> GtsEntity {
+        GtsEntity::new(
+            Some(json_file.clone()),
+            list_sequence,
+            content,
+            Some(&self.cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        )
+    }
+
+    fn note_invalid_id(&self, file_path: &Path, entity: &GtsEntity) {
+        let Some(id) = entity
+            .selected_entity_field
+            .as_deref()
+            .and_then(|field| entity.content.get(field))
+            .and_then(Value::as_str)
+        else {
+            return;
+        };
+        if let Err(e) = GtsID::new(id) {
+            // Files are parsed again when the ID and schema indexes are built
+            let mut invalid_ids = self.invalid_ids.borrow_mut();
+            if !invalid_ids
+                .iter()
+                .any(|invalid| invalid.path == file_path && invalid.index == entity.list_sequence)
+            {
+                invalid_ids.push(GtsInvalidId {
+                    path: file_path.to_path_buf(),
+                    index: entity.list_sequence,
+                    id: id.to_owned(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Like `parse_file`, but returns the entities cached by an earlier call while the
+    /// file's modification time is unchanged. The flag tells whether they were reused.
+    fn parse_file_cached(&self, file_path: &Path) -> Option<(Vec<GtsEntity>, bool)> {
+        let modified = fs::metadata(file_path).and_then(|m| m.modified()).ok();
+        if let Some(cached) = self
+            .file_cache
+            .borrow()
+            .get(file_path)
+            .filter(|cached| Some(cached.modified) == modified)
+        {
+            self.invalid_ids
+                .borrow_mut()
+                .extend(cached.invalid_ids.iter().cloned());
+            return Some((cached.entities.clone(), true));
+        }
+
+        let invalid_before = self.invalid_ids.borrow().len();
+        let Some(entities) = self.parse_file(file_path) else {
+            self.file_cache.borrow_mut().remove(file_path);
+            return None;
+        };
+        if let Some(modified) = modified {
+            let invalid_ids = self.invalid_ids.borrow()[invalid_before..].to_vec();
+            self.file_cache.borrow_mut().insert(
+                file_path.to_path_buf(),
+                CachedFile {
+                    modified,
+                    entities: entities.clone(),
+                    invalid_ids,
+                },
+            );
+        }
+        Some((entities, false))
+    }
+
     fn process_file(&self, file_path: &Path) -> Vec<GtsEntity> {
+        self.parse_file(file_path).unwrap_or_default()
+    }
+
+    /// Entities discovered in a file, or `None` if the file failed to parse.
+    #[allow(clippy::cognitive_complexity)]
+    fn parse_file(&self, file_path: &Path) -> Option<Vec<GtsEntity>> {
         let mut entities = Vec::new();
 
         match Self::load_json_file(file_path) {
             Ok(content) => {
-                let json_file = GtsFile::new(
-                    file_path.to_string_lossy().to_string(),
-                    file_path
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string(),
-                    content.clone(),
-                );
+                let json_file = Self::json_file(file_path, &content);
 
                 // Handle both single objects and arrays
                 if let Some(arr) = content.as_array() {
                     for (idx, item) in arr.iter().enumerate() {
-                        let entity = GtsEntity::new(
-                            Some(json_file.clone()),
-                            Some(idx),
-                            item,
-                            Some(&self.cfg),
-                            None,
-                            false,
-                            String::new(),
-                            None,
-                            None,
-                        );
+                        let entity = self.make_entity(&json_file, Some(idx), item);
+                        self.note_invalid_id(file_path, &entity);
                         if let Some(ref gts_id) = entity.gts_id {
                             tracing::debug!("- discovered entity: {}", gts_id.id);
                             entities.push(entity);
@@ -159,17 +513,8 @@ impl GtsFileReader {
                         }
                     }
                 } else {
-                    let entity = GtsEntity::new(
-                        Some(json_file),
-                        None,
-                        &content,
-                        Some(&self.cfg),
-                        None,
-                        false,
-                        String::new(),
-                        None,
-                        None,
-                    );
+                    let entity = self.make_entity(&json_file, None, &content);
+                    self.note_invalid_id(file_path, &entity);
                     if let Some(ref gts_id) = entity.gts_id {
                         tracing::debug!("- discovered entity: {}", gts_id.id);
                         entities.push(entity);
@@ -183,21 +528,68 @@ impl GtsFileReader {
                 }
             }
             Err(e) => {
-                // Skip files that can't be parsed
-                tracing::debug!("Failed to parse file {:?}: {}", file_path, e);
+                tracing::warn!("Failed to parse file {:?}: {}", file_path, e);
+                if self.cfg.fail_on_parse_error {
+                    let mut errors = self.parse_errors.borrow_mut();
+                    if !errors.iter().any(|(path, _)| path == file_path) {
+                        errors.push((file_path.to_path_buf(), e.to_string()));
+                    }
+                }
+                return None;
             }
         }
 
-        entities
+        Some(entities)
+    }
+}
+
+/// Matches a `/`-separated relative path against a glob where `*` matches within
+/// one path segment, `**` matches any number of segments and `?` one character.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    glob_match_segments(&pattern, &path)
+}
+
+fn glob_match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| glob_match_segments(rest, &path[i..])),
+        Some((segment_pattern, rest)) => path.split_first().is_some_and(|(segment, path_rest)| {
+            glob_match_segment(segment_pattern, segment) && glob_match_segments(rest, path_rest)
+        }),
     }
 }
 
+fn glob_match_segment(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p_idx, mut t_idx) = (0, 0);
+    // Position of the last '*' and the text position it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t_idx < text.len() {
+        if p_idx < pattern.len() && (pattern[p_idx] == '?' || pattern[p_idx] == text[t_idx]) {
+            p_idx += 1;
+            t_idx += 1;
+        } else if p_idx < pattern.len() && pattern[p_idx] == '*' {
+            backtrack = Some((p_idx, t_idx));
+            p_idx += 1;
+        } else if let Some((star_idx, matched_to)) = backtrack {
+            p_idx = star_idx + 1;
+            t_idx = matched_to + 1;
+            backtrack = Some((star_idx, matched_to + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p_idx..].iter().all(|&c| c == '*')
+}
+
 impl GtsReader for GtsFileReader {
     fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
-        if !self.initialized {
-            self.collect_files();
-            self.initialized = true;
-        }
+        self.start_pass();
 
         tracing::debug!(
             "Processing {} files from {:?}",
@@ -205,22 +597,671 @@ impl GtsReader for GtsFileReader {
             self.paths
         );
 
-        #[allow(clippy::needless_collect)]
-        let entities: Vec<GtsEntity> = self
-            .files
-            .iter()
-            .flat_map(|file_path| self.process_file(file_path))
-            .collect();
+        let mut stats = GtsReadStats {
+            files_scanned: self.files.len(),
+            ..GtsReadStats::default()
+        };
+        let mut entities = Vec::new();
+        for file_path in &self.files {
+            match self.parse_file_cached(file_path) {
+                Some((found, reused)) => {
+                    stats.files_parsed += 1;
+                    stats.files_reused += usize::from(reused);
+                    entities.extend(found);
+                }
+                None => stats.files_failed += 1,
+            }
+        }
+        // Forget files that were deleted or are no longer matched
+        let files: HashSet<&PathBuf> = self.files.iter().collect();
+        self.file_cache
+            .get_mut()
+            .retain(|path, _| files.contains(path));
+        stats.count_entities(&entities);
+        self.stats = stats;
+
+        // Reuse the parsed entities for random access instead of reparsing
+        if self.index.get().is_none() {
+            let _ = self.index.set(Self::build_index(&entities));
+        }
 
         Box::new(entities.into_iter())
     }
 
-    fn read_by_id(&self, _entity_id: &str) -> Option<GtsEntity> {
-        // For FileReader, we don't support random access by ID
-        None
+    /// Counts entities file by file, dropping each file's entities before reading the
+    /// next. `stats` is left as it was after the last `iter`.
+    fn count(&mut self) -> usize {
+        self.start_pass();
+        self.files
+            .iter()
+            .filter_map(|file_path| self.parse_file(file_path))
+            .map(|entities| entities.len())
+            .sum()
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        let (file_path, list_sequence) = self.entity_index().get(entity_id)?;
+
+        let content = match Self::load_json_file(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::debug!("Failed to parse file {:?}: {}", file_path, e);
+                return None;
+            }
+        };
+        let json_file = Self::json_file(file_path, &content);
+        let item = match list_sequence {
+            Some(idx) => content.as_array()?.get(*idx)?,
+            None => &content,
+        };
+
+        let entity = self.make_entity(&json_file, *list_sequence, item);
+        // The file may have changed since it was indexed
+        entity
+            .gts_id
+            .as_ref()
+            .is_some_and(|gts_id| gts_id.id == entity_id)
+            .then_some(entity)
     }
 
+    /// Rediscovers files on the next pass. The parsed files stay cached, so only new
+    /// files and files with a different modification time are parsed again.
     fn reset(&mut self) {
         self.initialized = false;
+        self.index = OnceCell::new();
+        self.schemas = OnceCell::new();
+        self.resolved_schemas.get_mut().clear();
+        self.parse_errors.get_mut().clear();
+        self.invalid_ids.get_mut().clear();
+        self.stats = GtsReadStats::default();
+    }
+}
+
+impl SchemaResolver for GtsFileReader {
+    fn resolve(&self, uri: &str) -> Option<Value> {
+        let schema_id = Self::normalize_ref(uri);
+        let schema = self.schema_index().get(schema_id).cloned();
+        if schema.is_none() && schema_id.starts_with("gts.") {
+            tracing::warn!("Unresolved $ref to {} in {:?}", uri, self.paths);
+        }
+        schema
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_files;
+    use serde_json::json;
+
+    #[test]
+    fn test_stats_counts_files_and_entities() {
+        let dir = write_files(
+            "stats",
+            &[
+                (
+                    "event.json",
+                    json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+                ),
+                (
+                    "types.json",
+                    json!([
+                        {"$id": "gts.x.core.events.event.v1~", "type": "object"},
+                        {"$id": "gts.x.core.events.audit.v1~", "type": "object"}
+                    ]),
+                ),
+                (
+                    "instances.json",
+                    json!([
+                        {"id": "gts.x.core.events.event.v1~x.app.ns.a.v1.0"},
+                        {"id": "gts.x.core.events.event.v1~x.app.ns.b.v1.0"},
+                        {"name": "no id"}
+                    ]),
+                ),
+            ],
+        );
+        fs::write(dir.join("broken.json"), "{").expect("test");
+        fs::write(dir.join("notes.txt"), "not scanned").expect("test");
+
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        assert_eq!(reader.stats(), GtsReadStats::default());
+        let _ = reader.iter().count();
+        assert_eq!(
+            reader.stats(),
+            GtsReadStats {
+                files_scanned: 4,
+                files_parsed: 3,
+                files_reused: 0,
+                files_failed: 1,
+                entities: 5,
+                distinct_types: 2,
+                instances: 2,
+            }
+        );
+
+        reader.reset();
+        assert_eq!(reader.stats(), GtsReadStats::default());
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_rescan_reparses_only_changed_files() {
+        let dir = write_files(
+            "rescan",
+            &[
+                (
+                    "a.json",
+                    json!({"$id": "gts.x.core.events.a.v1~", "rev": 1}),
+                ),
+                (
+                    "b.json",
+                    json!({"$id": "gts.x.core.events.b.v1~", "rev": 1}),
+                ),
+                (
+                    "c.json",
+                    json!({"$id": "gts.x.core.events.c.v1~", "rev": 1}),
+                ),
+            ],
+        );
+        let set_content = |file: &str, content: &Value, age_secs: u64| {
+            let path = dir.join(file);
+            fs::write(&path, content.to_string()).expect("test");
+            let modified = SystemTime::now() - std::time::Duration::from_secs(age_secs);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .expect("test")
+                .set_modified(modified)
+                .expect("test");
+        };
+        // Distinct mtimes regardless of the file system's timestamp resolution
+        set_content(
+            "b.json",
+            &json!({"$id": "gts.x.core.events.b.v1~", "rev": 1}),
+            60,
+        );
+
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let revs = |reader: &mut GtsFileReader| -> Vec<(String, i64)> {
+            let mut revs: Vec<(String, i64)> = reader
+                .iter()
+                .map(|e| {
+                    (
+                        e.gts_id.expect("test").id,
+                        e.content["rev"].as_i64().expect("test"),
+                    )
+                })
+                .collect();
+            revs.sort();
+            revs
+        };
+        assert_eq!(revs(&mut reader).len(), 3);
+        assert_eq!(reader.stats().files_reused, 0);
+
+        set_content(
+            "b.json",
+            &json!({"$id": "gts.x.core.events.b.v1~", "rev": 2}),
+            0,
+        );
+        fs::remove_file(dir.join("c.json")).expect("test");
+        fs::write(
+            dir.join("d.json"),
+            json!({"$id": "gts.x.core.events.d.v1~", "rev": 1}).to_string(),
+        )
+        .expect("test");
+        reader.reset();
+
+        assert_eq!(
+            revs(&mut reader),
+            vec![
+                ("gts.x.core.events.a.v1~".to_owned(), 1),
+                ("gts.x.core.events.b.v1~".to_owned(), 2),
+                ("gts.x.core.events.d.v1~".to_owned(), 1),
+            ]
+        );
+        let stats = reader.stats();
+        assert_eq!(stats.files_parsed, 3);
+        // Only a.json is unchanged; b.json changed and d.json is new
+        assert_eq!(stats.files_reused, 1);
+
+        // Without changes every file is reused
+        let _ = reader.iter().count();
+        assert_eq!(reader.stats().files_reused, 3);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_gzip_compressed_files_are_discovered() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let dir = write_files(
+            "gzip",
+            &[(
+                "plain.json",
+                json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+            )],
+        );
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(br#"{"id": "gts.x.core.events.event.v1~x.app.items.item.v1.0"}"#)
+            .expect("test");
+        fs::write(dir.join("item.json.gz"), encoder.finish().expect("test")).expect("test");
+        fs::write(dir.join("archive.tar.gz"), b"not json").expect("test");
+
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let mut ids: Vec<String> = reader
+            .iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            vec![
+                "gts.x.core.events.event.v1~",
+                "gts.x.core.events.event.v1~x.app.items.item.v1.0"
+            ]
+        );
+        assert_eq!(reader.files.len(), 2);
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_count_matches_iter() {
+        let dir = write_files(
+            "count",
+            &[
+                (
+                    "schema.json",
+                    json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+                ),
+                (
+                    "items.json",
+                    json!([
+                        {"id": "gts.x.core.events.event.v1~x.app.items.a.v1.0"},
+                        {"name": "no id"},
+                        {"id": "gts.x.core.events.event.v1~x.app.items.b.v1.0"}
+                    ]),
+                ),
+            ],
+        );
+        fs::write(dir.join("broken.json"), "[").expect("test");
+
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        assert_eq!(reader.count(), 3);
+        assert_eq!(reader.iter().count(), reader.count());
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_parse_errors_collected_in_strict_mode() {
+        let dir = write_files(
+            "parse_errors",
+            &[(
+                "valid.json",
+                json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+            )],
+        );
+        fs::write(dir.join("broken.json"), "{\"$id\": ").expect("test");
+        let path = vec![dir.to_string_lossy().to_string()];
+
+        let mut lenient = GtsFileReader::new(&path, None);
+        assert_eq!(lenient.iter().count(), 1);
+        assert!(lenient.parse_errors().is_empty());
+
+        let cfg = GtsConfig {
+            fail_on_parse_error: true,
+            ..GtsConfig::default()
+        };
+        let mut reader = GtsFileReader::new(&path, Some(cfg));
+        let ids: Vec<String> = reader
+            .iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        assert_eq!(ids, vec!["gts.x.core.events.event.v1~"]);
+
+        let errors = reader.parse_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.ends_with("broken.json"));
+        assert!(!errors[0].1.is_empty());
+
+        reader.reset();
+        assert!(reader.parse_errors().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_invalid_ids_not_duplicated_by_read_by_id() {
+        let dir = write_files(
+            "invalid_ids",
+            &[(
+                "events.json",
+                json!([
+                    {"id": "gts.x.core.events.event.v1~x.app.orders.created.v1.0"},
+                    {"id": "gts.x.core.events.event.v1~x.app.Orders.updated.v1.0"}
+                ]),
+            )],
+        );
+        let path = vec![dir.to_string_lossy().to_string()];
+
+        let mut reader = GtsFileReader::new(&path, None);
+        assert_eq!(reader.iter().count(), 1);
+        assert_eq!(reader.invalid_ids().len(), 1);
+
+        assert!(reader
+            .read_by_id("gts.x.core.events.event.v1~x.app.orders.created.v1.0")
+            .is_some());
+        assert!(reader
+            .resolve_schema("gts.x.core.events.event.v1~")
+            .is_none());
+
+        let invalid = reader.invalid_ids();
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].index, Some(1));
+        assert_eq!(
+            invalid[0].id,
+            "gts.x.core.events.event.v1~x.app.Orders.updated.v1.0"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_schema_inlines_cross_file_ref() {
+        let dir = write_files(
+            "cross_ref",
+            &[
+                (
+                    "address.json",
+                    json!({
+                        "$id": "gts.x.core.common.address.v1~",
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "type": "object",
+                        "properties": {"city": {"type": "string"}}
+                    }),
+                ),
+                (
+                    "user.json",
+                    json!({
+                        "$id": "gts.x.core.common.user.v1~",
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "type": "object",
+                        "properties": {
+                            "address": {"$ref": "gts://gts.x.core.common.address.v1~"},
+                            "other": {"$ref": "gts://gts.x.core.common.missing.v1~"}
+                        }
+                    }),
+                ),
+            ],
+        );
+
+        let reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let schema = reader
+            .resolve_schema("gts.x.core.common.user.v1~")
+            .expect("schema should be indexed");
+
+        assert_eq!(
+            schema["properties"]["address"],
+            json!({"type": "object", "properties": {"city": {"type": "string"}}})
+        );
+        // Unresolved refs are kept as-is
+        assert_eq!(
+            schema["properties"]["other"],
+            json!({"$ref": "gts://gts.x.core.common.missing.v1~"})
+        );
+        assert!(reader
+            .resolve_schema("gts.x.core.common.missing.v1~")
+            .is_none());
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.json", "a.json"));
+        assert!(!glob_match("*.json", "sub/a.json"));
+        assert!(glob_match("**/*.json", "a.json"));
+        assert!(glob_match("**/*.json", "sub/deep/a.json"));
+        assert!(glob_match("schemas/**", "schemas/x/y.yaml"));
+        assert!(glob_match("schemas/v?/*.json", "schemas/v1/a.json"));
+        assert!(!glob_match("schemas/v?/*.json", "schemas/v10/a.json"));
+        assert!(glob_match("*event*.json", "my_event_v1.json"));
+    }
+
+    #[test]
+    fn test_reader_with_builder_config() {
+        let dir = write_files(
+            "builder_config",
+            &[
+                ("event.gts", json!({"key": "gts.x.core.events.event.v1~"})),
+                ("topic.json", json!({"key": "gts.x.core.events.topic.v1~"})),
+            ],
+        );
+        let cfg = GtsConfig::builder()
+            .id_field("key")
+            .extensions([".gts"])
+            .build()
+            .expect("test");
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], Some(cfg));
+        let ids: Vec<String> = reader
+            .iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        assert_eq!(ids, vec!["gts.x.core.events.event.v1~"]);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_collect_files_with_custom_extensions() {
+        let dir = write_files(
+            "extensions",
+            &[
+                (
+                    "event.GTSX",
+                    json!({"$id": "gts.x.core.events.event.v1~", "type": "object"}),
+                ),
+                (
+                    "topic.schema.json",
+                    json!({"$id": "gts.x.core.events.topic.v1~", "type": "object"}),
+                ),
+                (
+                    "item.json",
+                    json!({"id": "gts.x.core.events.event.v1~x.app.items.item.v1.0"}),
+                ),
+                ("notes.yaml", json!({})),
+            ],
+        );
+        let cfg = GtsConfig {
+            extensions: vec!["GtsX".to_owned(), " .schema.json".to_owned()],
+            ..GtsConfig::default()
+        };
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], Some(cfg));
+        let mut ids: Vec<String> = reader
+            .iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            vec!["gts.x.core.events.event.v1~", "gts.x.core.events.topic.v1~"]
+        );
+        assert_eq!(reader.stats().files_scanned, 2);
+
+        let mut default_reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        assert_eq!(default_reader.iter().count(), 2);
+        assert_eq!(default_reader.stats().files_scanned, 3);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_collect_files_with_excludes_and_globs() {
+        let dir = write_files("excludes", &[("a.json", json!({})), ("b.yaml", json!({}))]);
+        for sub in ["node_modules", "vendor", "schemas/events"] {
+            fs::create_dir_all(dir.join(sub)).expect("test");
+            fs::write(dir.join(sub).join("x.json"), "{}").expect("test");
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, dir.join("vendor").join("loop")).expect("test");
+
+        let names = |cfg: GtsConfig| -> Vec<String> {
+            let reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], Some(cfg));
+            let mut names: Vec<String> = reader
+                .discover_files()
+                .iter()
+                .map(|f| {
+                    f.strip_prefix(dir.canonicalize().expect("test"))
+                        .expect("test")
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect();
+            names.sort();
+            names
+        };
+
+        let default_cfg = GtsConfig::default();
+        assert_eq!(
+            names(default_cfg.clone()),
+            vec!["a.json", "b.yaml", "schemas/events/x.json", "vendor/x.json"]
+        );
+
+        let cfg = GtsConfig {
+            exclude_dirs: vec!["vendor".to_owned()],
+            ..default_cfg.clone()
+        };
+        assert_eq!(
+            names(cfg),
+            vec!["a.json", "b.yaml", "schemas/events/x.json"]
+        );
+
+        let cfg = GtsConfig {
+            exclude_dirs: vec!["vendor".to_owned()],
+            replace_default_excludes: true,
+            ..default_cfg.clone()
+        };
+        assert_eq!(
+            names(cfg),
+            vec![
+                "a.json",
+                "b.yaml",
+                "node_modules/x.json",
+                "schemas/events/x.json"
+            ]
+        );
+
+        let cfg = GtsConfig {
+            include_globs: vec!["schemas/**/*.json".to_owned(), "*.yaml".to_owned()],
+            ..default_cfg
+        };
+        assert_eq!(names(cfg), vec!["b.yaml", "schemas/events/x.json"]);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_read_by_id_uses_index() {
+        let dir = write_files(
+            "read_by_id",
+            &[
+                (
+                    "single.json",
+                    json!({"id": "gts.x.core.events.event.v1~x.core.test.single.v1"}),
+                ),
+                (
+                    "list.json",
+                    json!([
+                        {"id": "gts.x.core.events.event.v1~x.core.test.first.v1"},
+                        {"id": "gts.x.core.events.event.v1~x.core.test.second.v1"}
+                    ]),
+                ),
+            ],
+        );
+
+        let mut reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+
+        // Works before the reader has been iterated
+        let entity = reader
+            .read_by_id("gts.x.core.events.event.v1~x.core.test.second.v1")
+            .expect("entity should be indexed");
+        assert_eq!(entity.list_sequence, Some(1));
+        assert_eq!(entity.label, "list.json#1");
+
+        // And after
+        assert_eq!(reader.iter().count(), 3);
+        let entity = reader
+            .read_by_id("gts.x.core.events.event.v1~x.core.test.single.v1")
+            .expect("entity should be indexed");
+        assert_eq!(entity.list_sequence, None);
+        assert!(reader
+            .read_by_id("gts.x.core.events.event.v1~x.core.test.none.v1")
+            .is_none());
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_read_by_id_duplicate_returns_first() {
+        let dir = write_files(
+            "read_by_id_dup",
+            &[(
+                "list.json",
+                json!([
+                    {"id": "gts.x.core.events.event.v1~x.core.test.dup.v1", "n": 1},
+                    {"id": "gts.x.core.events.event.v1~x.core.test.dup.v1", "n": 2}
+                ]),
+            )],
+        );
+
+        let reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let entity = reader
+            .read_by_id("gts.x.core.events.event.v1~x.core.test.dup.v1")
+            .expect("test");
+        assert_eq!(entity.content["n"], json!(1));
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_resolve_schema_keeps_cyclic_refs() {
+        let dir = write_files(
+            "cyclic_ref",
+            &[
+                (
+                    "a.json",
+                    json!({
+                        "$id": "gts.x.core.common.a.v1~",
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "type": "object",
+                        "properties": {"b": {"$ref": "gts://gts.x.core.common.b.v1~"}}
+                    }),
+                ),
+                (
+                    "b.json",
+                    json!({
+                        "$id": "gts.x.core.common.b.v1~",
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "type": "object",
+                        "properties": {"a": {"$ref": "gts://gts.x.core.common.a.v1~"}}
+                    }),
+                ),
+            ],
+        );
+
+        let reader = GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let schema = reader
+            .resolve_schema("gts.x.core.common.a.v1~")
+            .expect("test");
+
+        assert_eq!(
+            schema["properties"]["b"],
+            json!({"$ref": "gts://gts.x.core.common.b.v1~"})
+        );
+
+        fs::remove_dir_all(&dir).expect("test");
     }
 }
@@ -1,11 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
-use std::sync::LazyLock;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::path_resolver::parse_attribute_path;
+
 pub const GTS_PREFIX: &str = "gts.";
-static GTS_NS: LazyLock<Uuid> = LazyLock::new(|| Uuid::new_v5(&Uuid::NAMESPACE_URL, b"gts"));
+/// Stand-in for a single-token wildcard while the rest of a pattern is parsed.
+const TOKEN_PLACEHOLDER: &str = "_";
+/// Default namespace for `GtsID::to_uuid`: the UUID v5 of `gts` in the URL namespace.
+pub const GTS_UUID_NAMESPACE: Uuid = Uuid::from_u128(0x63b0_6280_5dd6_517d_abc6_5a21_27e8_43c3);
 
 /// Validates a GTS segment token without regex for better performance.
 /// Valid tokens: start with [a-z_], followed by [a-z0-9_]*
@@ -24,7 +31,12 @@ fn is_valid_segment_token(token: &str) -> bool {
     chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
 }
 
-#[derive(Debug, Error)]
+/// Errors raised while parsing GTS identifiers and wildcard patterns.
+///
+/// Serializes to an object with a `kind` discriminator (`invalid_segment`, `invalid_id`,
+/// `invalid_wildcard`) alongside the variant fields.
+#[derive(Debug, Error, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum GtsError {
     #[error("Invalid GTS segment #{num} @ offset {offset}: '{segment}': {cause}")]
     InvalidSegment {
@@ -41,6 +53,55 @@ pub enum GtsError {
     InvalidWildcard { pattern: String, cause: String },
 }
 
+impl GtsError {
+    /// Renders the error with `input`, the string that failed to parse, and a line of
+    /// carets under the offending segment:
+    ///
+    /// ```text
+    /// Invalid GTS segment #2 @ offset 27: 'x.app.orders.9created.v1': ...
+    ///   gts.x.core.events.event.v1~x.app.orders.9created.v1
+    ///                              ^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// Returns `None` for errors without an offset or one that does not fall within
+    /// `input`.
+    #[must_use]
+    pub fn offset_context(&self, input: &str) -> Option<String> {
+        let GtsError::InvalidSegment {
+            offset, segment, ..
+        } = self
+        else {
+            return None;
+        };
+        // Offsets count from the start of the trimmed ID
+        let start = offset + (input.len() - input.trim_start().len());
+        let column = input.get(..start)?.chars().count();
+        let width = segment.chars().count().max(1);
+        Some(format!(
+            "{self}\n  {input}\n  {}{}",
+            " ".repeat(column),
+            "^".repeat(width)
+        ))
+    }
+}
+
+/// Renders a parsed segment from its fields; wildcard segments keep their text.
+fn canonical_segment(seg: &GtsIdSegment) -> String {
+    if seg.is_wildcard {
+        return seg.segment.clone();
+    }
+    let marker = if seg.is_type { "~" } else { "" };
+    let names = format!(
+        "{}.{}.{}.{}",
+        seg.vendor, seg.package, seg.namespace, seg.type_name
+    );
+    if seg.is_versionless() {
+        return format!("{names}{marker}");
+    }
+    let minor = seg.ver_minor.map(|m| format!(".{m}")).unwrap_or_default();
+    format!("{names}.v{}{minor}{marker}", seg.ver_major)
+}
+
 /// Parsed GTS segment containing vendor, package, namespace, type, and version info.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GtsIdSegment {
@@ -82,6 +143,15 @@ impl GtsIdSegment {
         Ok(seg)
     }
 
+    /// Returns true for a type reference without version tokens, such as
+    /// `x.core.events.event~`, which refers to any version of the type.
+    #[must_use]
+    pub fn is_versionless(&self) -> bool {
+        self.is_type
+            && !self.is_wildcard
+            && self.segment.trim_end_matches('~').split('.').count() == 4
+    }
+
     #[allow(clippy::too_many_lines)]
     fn parse_segment_id(&mut self, segment: &str) -> Result<(), GtsError> {
         let mut segment = segment.to_owned();
@@ -121,7 +191,10 @@ impl GtsIdSegment {
             });
         }
 
-        if !segment.ends_with('*') && tokens.len() < 5 {
+        // A type reference may omit the version tokens to mean "any version"
+        let is_versionless_type = self.is_type && tokens.len() == 4;
+
+        if !segment.ends_with('*') && tokens.len() < 5 && !is_versionless_type {
             return Err(GtsError::InvalidSegment {
                 num: self.num,
                 offset: self.offset,
@@ -239,6 +312,109 @@ impl GtsIdSegment {
     }
 }
 
+/// Minor versions accepted by `GtsID::version_compatible_with`. The majors must
+/// always be equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// The minor versions must be equal.
+    ExactMinor,
+    /// The minor version must not exceed the expected one.
+    AtMostMinor,
+    /// Any minor version.
+    SameMajor,
+}
+
+/// Limits applied by `GtsID::new_with_options` when parsing an identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtsParseOptions {
+    /// Maximum length of the whole identifier in bytes.
+    pub max_length: usize,
+    /// Maximum number of chained segments, or `None` for no limit.
+    pub max_segments: Option<usize>,
+    /// Whether the identifier may end with the `~` type marker.
+    pub allow_type_marker: bool,
+}
+
+impl Default for GtsParseOptions {
+    fn default() -> Self {
+        GtsParseOptions {
+            max_length: 1024,
+            max_segments: None,
+            allow_type_marker: true,
+        }
+    }
+}
+
+/// Parses GTS IDs while caching parsed segments by their text, so segment strings
+/// that recur across a large ID set are validated only once.
+///
+/// The cache grows with the number of distinct segments; call `clear` to release it.
+#[derive(Debug, Default)]
+pub struct GtsParser {
+    options: GtsParseOptions,
+    segments: HashMap<String, GtsIdSegment>,
+    segments_parsed: usize,
+}
+
+impl GtsParser {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_options(options: GtsParseOptions) -> Self {
+        GtsParser {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Parse and validate a GTS identifier string, as `GtsID::new_with_options` does.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the string is not a valid GTS identifier or
+    /// exceeds the limits of the parser's options.
+    pub fn parse(&mut self, id: &str) -> Result<GtsID, GtsError> {
+        let GtsParser {
+            options,
+            segments,
+            segments_parsed,
+        } = self;
+        GtsID::parse(id, options, |num, offset, part| {
+            if let Some(cached) = segments.get(part) {
+                return Ok(GtsIdSegment {
+                    num,
+                    offset,
+                    ..cached.clone()
+                });
+            }
+            *segments_parsed += 1;
+            let seg = GtsIdSegment::new(num, offset, part)?;
+            segments.insert(part.to_owned(), seg.clone());
+            Ok(seg)
+        })
+    }
+
+    /// Number of distinct segments held in the cache.
+    #[must_use]
+    pub fn cached_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Number of segments parsed from scratch because they were not cached.
+    #[must_use]
+    pub fn segments_parsed(&self) -> usize {
+        self.segments_parsed
+    }
+
+    /// Drops all cached segments and resets the counters.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.segments_parsed = 0;
+    }
+}
+
 /// GTS ID - a validated Global Type System identifier.
 ///
 /// GTS IDs follow the format: `gts.<vendor>.<package>.<namespace>.<type>.<version>[~]`
@@ -255,72 +431,171 @@ impl GtsID {
     /// # Errors
     /// Returns `GtsError::InvalidId` if the string is not a valid GTS identifier.
     pub fn new(id: &str) -> Result<Self, GtsError> {
+        Self::new_with_options(id, &GtsParseOptions::default())
+    }
+
+    /// Parse and validate a GTS identifier string using custom limits.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the string is not a valid GTS identifier or
+    /// exceeds the limits in `options`.
+    pub fn new_with_options(id: &str, options: &GtsParseOptions) -> Result<Self, GtsError> {
+        Self::parse(id, options, GtsIdSegment::new)
+    }
+
+    /// Parse and validate a GTS identifier string, reusing segments already parsed
+    /// by `parser`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the string is not a valid GTS identifier or
+    /// exceeds the limits of the parser's options.
+    pub fn new_with_parser(id: &str, parser: &mut GtsParser) -> Result<Self, GtsError> {
+        parser.parse(id)
+    }
+
+    fn parse(
+        id: &str,
+        options: &GtsParseOptions,
+        parse_segment: impl FnMut(usize, usize, &str) -> Result<GtsIdSegment, GtsError>,
+    ) -> Result<Self, GtsError> {
+        let (gts_id_segments, errors) = Self::check(id, options, parse_segment, true);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+
+        Ok(GtsID {
+            id: id.trim().to_owned(),
+            gts_id_segments,
+        })
+    }
+
+    /// Validates `id` and returns every error found, or an empty vec when `id` is a
+    /// valid GTS identifier.
+    ///
+    /// Unlike `GtsID::new`, which stops at the first problem, the casing, length
+    /// and type marker checks and every segment are all checked, so an ID with two
+    /// invalid segments reports both. Segments are only checked when the input
+    /// starts with `gts.`.
+    #[must_use]
+    pub fn parse_diagnostics(input: &str) -> Vec<GtsError> {
+        Self::check(input, &GtsParseOptions::default(), GtsIdSegment::new, false).1
+    }
+
+    /// Checks `id` and parses its segments. With `fail_fast` the check stops at the
+    /// first error, otherwise it goes on wherever later checks still make sense.
+    fn check(
+        id: &str,
+        options: &GtsParseOptions,
+        mut parse_segment: impl FnMut(usize, usize, &str) -> Result<GtsIdSegment, GtsError>,
+        fail_fast: bool,
+    ) -> (Vec<GtsIdSegment>, Vec<GtsError>) {
         let raw = id.trim();
+        let invalid = |cause: String| GtsError::InvalidId {
+            id: id.to_owned(),
+            cause,
+        };
+        let mut gts_id_segments = Vec::new();
+        let mut errors = Vec::new();
 
         // Validate lowercase
         if raw != raw.to_lowercase() {
-            return Err(GtsError::InvalidId {
-                id: id.to_owned(),
-                cause: "Must be lower case".to_owned(),
-            });
+            errors.push(invalid("Must be lower case".to_owned()));
         }
 
         if raw.contains('-') {
-            return Err(GtsError::InvalidId {
-                id: id.to_owned(),
-                cause: "Must not contain '-'".to_owned(),
-            });
+            errors.push(invalid("Must not contain '-'".to_owned()));
         }
 
         if !raw.starts_with(GTS_PREFIX) {
-            return Err(GtsError::InvalidId {
-                id: id.to_owned(),
-                cause: format!("Does not start with '{GTS_PREFIX}'"),
-            });
+            errors.push(invalid(format!("Does not start with '{GTS_PREFIX}'")));
+            return (gts_id_segments, errors);
         }
 
-        if raw.len() > 1024 {
-            return Err(GtsError::InvalidId {
-                id: id.to_owned(),
-                cause: "Too long".to_owned(),
-            });
+        if raw.len() > options.max_length {
+            errors.push(invalid("Too long".to_owned()));
+        }
+
+        if !options.allow_type_marker && raw.ends_with('~') {
+            errors.push(invalid(
+                "Trailing '~' type marker is not allowed".to_owned(),
+            ));
+        }
+
+        if fail_fast && !errors.is_empty() {
+            return (gts_id_segments, errors);
         }
 
-        let mut gts_id_segments = Vec::new();
         let remainder = &raw[GTS_PREFIX.len()..];
 
-        // Split by ~ preserving empties to detect trailing ~
-        let tilde_parts: Vec<&str> = remainder.split('~').collect();
-        let mut parts = Vec::new();
+        // Every segment keeps its own trailing '~' type marker, which `GtsIdSegment`
+        // validates; only the last segment may lack one
+        let parts: Vec<&str> = remainder.split_inclusive('~').collect();
+        if parts.is_empty() {
+            errors.push(invalid(format!(
+                "GTS segment #1 @ offset {} is empty",
+                GTS_PREFIX.len()
+            )));
+            return (gts_id_segments, errors);
+        }
 
-        for i in 0..tilde_parts.len() {
-            if i < tilde_parts.len() - 1 {
-                parts.push(format!("{}~", tilde_parts[i]));
-                if i == tilde_parts.len() - 2 && tilde_parts[i + 1].is_empty() {
-                    break;
+        if let Some(max_segments) = options.max_segments {
+            if parts.len() > max_segments {
+                errors.push(invalid(format!("Too many segments (max {max_segments})")));
+                if fail_fast {
+                    return (gts_id_segments, errors);
                 }
-            } else {
-                parts.push(tilde_parts[i].to_owned());
             }
         }
 
         let mut offset = GTS_PREFIX.len();
         for (i, part) in parts.iter().enumerate() {
-            if part.is_empty() || part == "~" {
-                return Err(GtsError::InvalidId {
-                    id: id.to_owned(),
-                    cause: format!("GTS segment #{} @ offset {offset} is empty", i + 1),
-                });
+            if *part == "~" {
+                let reason = if i == 0 { "stray" } else { "doubled" };
+                errors.push(invalid(format!(
+                    "GTS segment #{} @ offset {offset} is empty: {reason} '~'",
+                    i + 1
+                )));
+            } else {
+                match parse_segment(i + 1, offset, part) {
+                    Ok(segment) => gts_id_segments.push(segment),
+                    Err(e) => errors.push(e),
+                }
+            }
+            if fail_fast && !errors.is_empty() {
+                break;
             }
-
-            gts_id_segments.push(GtsIdSegment::new(i + 1, offset, part)?);
             offset += part.len();
         }
 
-        Ok(GtsID {
-            id: raw.to_owned(),
-            gts_id_segments,
-        })
+        (gts_id_segments, errors)
+    }
+
+    /// Parse a GTS identifier after lowercasing it.
+    ///
+    /// Returns the parsed ID and whether the input had to be normalized. Use
+    /// `GtsID::new` for strict validation.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the lowercased string is not a valid GTS identifier.
+    pub fn new_lenient(id: &str) -> Result<(Self, bool), GtsError> {
+        let raw = id.trim();
+        let normalized = raw.to_lowercase();
+        let was_normalized = normalized != raw;
+        Ok((Self::new(&normalized)?, was_normalized))
+    }
+
+    /// Parses `input` and re-emits it in canonical form built from the parsed
+    /// segments, e.g. with whitespace around chained segments removed.
+    ///
+    /// The result is stable across equivalent inputs, which makes it suitable as a
+    /// map key or as input to `to_uuid`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if `input` is not a valid GTS identifier.
+    pub fn normalize(input: &str) -> Result<String, GtsError> {
+        let id = Self::new(input)?;
+        let segments: String = id.gts_id_segments.iter().map(canonical_segment).collect();
+        Ok(format!("{GTS_PREFIX}{segments}"))
     }
 
     #[must_use] 
@@ -330,21 +605,332 @@ impl GtsID {
 
     #[must_use] 
     pub fn get_type_id(&self) -> Option<String> {
-        if self.gts_id_segments.len() < 2 {
-            return None;
-        }
-        let segments: String = self.gts_id_segments[..self.gts_id_segments.len() - 1]
-            .iter()
+        let parents = self.segment_count().checked_sub(1).filter(|n| *n > 0)?;
+        let segments: String = self
+            .segments_iter()
+            .take(parents)
             .map(|s| s.segment.as_str())
             .collect::<Vec<_>>()
             .join("");
         Some(format!("{GTS_PREFIX}{segments}"))
     }
 
+    /// Iterates over the parsed segments from the root to the last one.
+    pub fn segments_iter(&self) -> std::slice::Iter<'_, GtsIdSegment> {
+        self.gts_id_segments.iter()
+    }
+
+    /// Number of chained segments.
+    #[must_use]
+    pub fn segment_count(&self) -> usize {
+        self.gts_id_segments.len()
+    }
+
+    /// The segment at `idx`, counting from the root, or `None` when out of range.
+    #[must_use]
+    pub fn segment(&self, idx: usize) -> Option<&GtsIdSegment> {
+        self.gts_id_segments.get(idx)
+    }
+
+    /// The segment `n` places before the last one (0 is the last segment), or
+    /// `None` when out of range.
+    #[must_use]
+    pub fn nth_from_end(&self, n: usize) -> Option<&GtsIdSegment> {
+        let last = self.segment_count().checked_sub(1)?;
+        self.segment(last.checked_sub(n)?)
+    }
+
+    /// The first (root) segment of the chain.
+    #[must_use]
+    pub fn root_segment(&self) -> Option<&GtsIdSegment> {
+        self.segment(0)
+    }
+
+    /// The last segment of the chain, which identifies the entity itself.
+    #[must_use]
+    pub fn last_segment(&self) -> Option<&GtsIdSegment> {
+        self.nth_from_end(0)
+    }
+
+    /// Vendor, package, namespace and type name of the last segment.
+    #[must_use]
+    pub fn type_identity(&self) -> Option<(&str, &str, &str, &str)> {
+        self.last_segment().map(|seg| {
+            (
+                seg.vendor.as_str(),
+                seg.package.as_str(),
+                seg.namespace.as_str(),
+                seg.type_name.as_str(),
+            )
+        })
+    }
+
+    /// Builds a wildcard matching this ID's type at any minor version.
+    ///
+    /// The minor version is dropped from the last segment, so
+    /// `gts.x.core.events.event.v1.2` becomes `gts.x.core.events.event.v1`, which
+    /// matches `v1.0`, `v1.5`, etc. of the same type.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidWildcard` if the resulting pattern is invalid.
+    pub fn to_type_wildcard(&self) -> Result<GtsWildcard, GtsError> {
+        let Some((last, parents)) = self.gts_id_segments.split_last() else {
+            return GtsWildcard::new(&self.id);
+        };
+
+        let last_segment = if last.is_wildcard || last.is_versionless() || last.ver_minor.is_none()
+        {
+            last.segment.clone()
+        } else {
+            format!(
+                "{}.{}.{}.{}.v{}{}",
+                last.vendor,
+                last.package,
+                last.namespace,
+                last.type_name,
+                last.ver_major,
+                if last.is_type { "~" } else { "" }
+            )
+        };
+        let parents: String = parents.iter().map(|seg| seg.segment.as_str()).collect();
+
+        GtsWildcard::new(&format!("{GTS_PREFIX}{parents}{last_segment}"))
+    }
+
+    /// Returns this ID with the last segment's minor version incremented; an absent
+    /// minor becomes `1`, so `v1` bumps to `v1.1`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the last segment is a wildcard or versionless,
+    /// or the version overflows.
+    pub fn bump_minor(&self) -> Result<GtsID, GtsError> {
+        let last = self.bumpable_segment()?;
+        let minor = match last.ver_minor {
+            Some(minor) => minor.checked_add(1).ok_or_else(|| self.overflow_error())?,
+            None => 1,
+        };
+        self.with_last_version(last.ver_major, Some(minor))
+    }
+
+    /// Returns this ID with the last segment's major version incremented. The minor
+    /// version resets to `0` when present and stays absent otherwise, so `v1.4`
+    /// bumps to `v2.0` and `v1` to `v2`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the last segment is a wildcard or versionless,
+    /// or the version overflows.
+    pub fn bump_major(&self) -> Result<GtsID, GtsError> {
+        let last = self.bumpable_segment()?;
+        let major = last
+            .ver_major
+            .checked_add(1)
+            .ok_or_else(|| self.overflow_error())?;
+        self.with_last_version(major, last.ver_minor.map(|_| 0))
+    }
+
+    /// Returns this ID with a trailing `~` on the last segment, turning an instance ID
+    /// into the matching type ID. Earlier segments are kept as they are, and a type
+    /// ID is returned unchanged.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidId` if the resulting ID does not validate.
+    pub fn with_type_marker(&self) -> Result<GtsID, GtsError> {
+        self.with_last_type_marker(true)
+    }
+
+    /// Returns this ID with the trailing `~` removed from the last segment, turning a
+    /// type ID into the matching instance ID. Earlier segments are kept as they are,
+    /// and an instance ID is returned unchanged.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidSegment` if the last segment is only valid as a type,
+    /// such as the versionless `x.core.events.event~`.
+    pub fn without_type_marker(&self) -> Result<GtsID, GtsError> {
+        self.with_last_type_marker(false)
+    }
+
+    fn with_last_type_marker(&self, is_type: bool) -> Result<GtsID, GtsError> {
+        if self.is_type() == is_type {
+            return Ok(self.clone());
+        }
+        let Some((last, parents)) = self.gts_id_segments.split_last() else {
+            return Err(GtsError::InvalidId {
+                id: self.id.clone(),
+                cause: "No segments".to_owned(),
+            });
+        };
+        let parents: String = parents.iter().map(|seg| seg.segment.as_str()).collect();
+        let last = last.segment.trim_end_matches('~');
+        GtsID::new(&format!(
+            "{GTS_PREFIX}{parents}{last}{}",
+            if is_type { "~" } else { "" }
+        ))
+    }
+
+    fn overflow_error(&self) -> GtsError {
+        GtsError::InvalidId {
+            id: self.id.clone(),
+            cause: "Version overflow".to_owned(),
+        }
+    }
+
+    fn bumpable_segment(&self) -> Result<&GtsIdSegment, GtsError> {
+        match self.last_segment() {
+            Some(last) if !last.is_wildcard && !last.is_versionless() => Ok(last),
+            _ => Err(GtsError::InvalidId {
+                id: self.id.clone(),
+                cause: "Last segment has no version to bump".to_owned(),
+            }),
+        }
+    }
+
+    fn with_last_version(&self, major: u32, minor: Option<u32>) -> Result<GtsID, GtsError> {
+        let Some((last, parents)) = self.gts_id_segments.split_last() else {
+            return Err(GtsError::InvalidId {
+                id: self.id.clone(),
+                cause: "No segments".to_owned(),
+            });
+        };
+        let minor = minor.map(|m| format!(".{m}")).unwrap_or_default();
+        let parents: String = parents.iter().map(|seg| seg.segment.as_str()).collect();
+        GtsID::new(&format!(
+            "{GTS_PREFIX}{parents}{}.{}.{}.{}.v{major}{minor}{}",
+            last.vendor,
+            last.package,
+            last.namespace,
+            last.type_name,
+            if last.is_type { "~" } else { "" }
+        ))
+    }
+
+    /// Builds a wildcard keeping the first `depth` tokens of this ID and appending `*`.
+    ///
+    /// Tokens are the parts separated by `.` or `~` after the `gts.` prefix, so depth
+    /// 2 of `gts.x.core.events.event.v1~` gives `gts.x.core.*`.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidWildcard` if `depth` leaves no separator to append
+    /// `*` after, or the resulting pattern is invalid.
+    pub fn to_prefix_wildcard(&self, depth: usize) -> Result<GtsWildcard, GtsError> {
+        if depth == 0 {
+            return GtsWildcard::new(&format!("{GTS_PREFIX}*"));
+        }
+
+        let remainder = &self.id[GTS_PREFIX.len()..];
+        let Some((sep_pos, _)) = remainder
+            .char_indices()
+            .filter(|(_, c)| *c == '.' || *c == '~')
+            .nth(depth - 1)
+        else {
+            return Err(GtsError::InvalidWildcard {
+                pattern: self.id.clone(),
+                cause: format!("Cannot truncate to {depth} tokens"),
+            });
+        };
+
+        let prefix_len = GTS_PREFIX.len() + sep_pos + 1;
+        GtsWildcard::new(&format!("{}*", &self.id[..prefix_len]))
+    }
+
+    /// Compares the version of this ID's last segment with `other`'s.
+    ///
+    /// Major versions are compared first, then minor versions. Returns `None` when
+    /// the last segments name different types, when either is versionless, or when
+    /// the majors are equal but only one side specifies a minor version.
+    #[must_use]
+    pub fn compare_version(&self, other: &GtsID) -> Option<Ordering> {
+        if self.type_identity()? != other.type_identity()? {
+            return None;
+        }
+
+        let a = self.last_segment()?;
+        let b = other.last_segment()?;
+        if a.is_versionless() || b.is_versionless() {
+            return None;
+        }
+
+        match a.ver_major.cmp(&b.ver_major) {
+            Ordering::Equal => match (a.ver_minor, b.ver_minor) {
+                (Some(a_minor), Some(b_minor)) => Some(a_minor.cmp(&b_minor)),
+                (None, None) => Some(Ordering::Equal),
+                _ => None,
+            },
+            ordering => Some(ordering),
+        }
+    }
+
+    /// Returns true when both IDs have the same segment chain apart from minor versions.
+    ///
+    /// Every segment is compared on vendor, package, namespace, type name, major
+    /// version and the `is_type` marker. Use `==` for exact equality.
+    #[must_use]
+    pub fn same_type(&self, other: &GtsID) -> bool {
+        self.segment_count() == other.segment_count()
+            && self
+                .segments_iter()
+                .zip(other.segments_iter())
+                .all(|(a, b)| {
+                    a.vendor == b.vendor
+                        && a.package == b.package
+                        && a.namespace == b.namespace
+                        && a.type_name == b.type_name
+                        && a.ver_major == b.ver_major
+                        && a.is_type == b.is_type
+                })
+    }
+
+    /// Returns true when an entity with this ID can be used where `other` is expected,
+    /// judged from the IDs alone.
+    ///
+    /// Both IDs must pass [`GtsID::same_type`]; `policy` then decides which minor
+    /// versions are accepted, segment by segment. A segment without a minor version
+    /// counts as minor 0. This is a cheap pre-filter, not a substitute for checking
+    /// schema compatibility.
+    #[must_use]
+    pub fn version_compatible_with(&self, other: &GtsID, policy: VersionPolicy) -> bool {
+        self.same_type(other)
+            && self
+                .segments_iter()
+                .zip(other.segments_iter())
+                .all(|(a, b)| {
+                    let (a_minor, b_minor) = (a.ver_minor.unwrap_or(0), b.ver_minor.unwrap_or(0));
+                    match policy {
+                        VersionPolicy::ExactMinor => a_minor == b_minor,
+                        VersionPolicy::AtMostMinor => a_minor <= b_minor,
+                        VersionPolicy::SameMajor => true,
+                    }
+                })
+    }
+
+    /// Returns true when this ID's segment chain is a strict prefix of `other`'s.
+    ///
+    /// Segments are compared as in [`GtsID::wildcard_match`]: vendor, package, namespace,
+    /// type name and major version must match, while a minor version is only checked
+    /// when this ID specifies one. The `is_type` marker must match too, so only a type ID
+    /// (ending in `~`) can be an ancestor; an ID is not its own ancestor.
+    #[must_use]
+    pub fn is_ancestor_of(&self, other: &GtsID) -> bool {
+        self.segment_count() < other.segment_count()
+            && self
+                .segments_iter()
+                .zip(other.segments_iter())
+                .all(|(a, b)| Self::match_exact_segment(a, b, None, false))
+    }
+
     /// Generate a deterministic UUID v5 from this GTS ID.
+    ///
+    /// The UUID is derived from `id` as given, so apply [`GtsID::normalize`] first
+    /// when equivalent spellings of an ID must map to the same UUID.
     #[must_use]
     pub fn to_uuid(&self) -> Uuid {
-        Uuid::new_v5(&GTS_NS, self.id.as_bytes())
+        self.to_uuid_with_namespace(&GTS_UUID_NAMESPACE)
+    }
+
+    /// Generate a deterministic UUID v5 from this GTS ID under a caller-chosen
+    /// namespace, for systems that expect IDs namespaced under their own URL.
+    #[must_use]
+    pub fn to_uuid_with_namespace(&self, namespace: &Uuid) -> Uuid {
+        Uuid::new_v5(namespace, self.id.as_bytes())
     }
 
     /// Check if a string is a valid GTS identifier.
@@ -357,97 +943,122 @@ impl GtsID {
     }
 
     /// Check if this GTS ID matches a wildcard pattern.
+    ///
+    /// Uses the wildcard position computed when the pattern was built, so the pattern
+    /// string is not inspected here.
     #[must_use]
     pub fn wildcard_match(&self, pattern: &GtsWildcard) -> bool {
-        let p = &pattern.id;
+        let pattern_segs = &pattern.gts_id_segments;
+        let candidate_segs = &self.gts_id_segments;
 
-        // No wildcard case - need exact match with version flexibility
-        if !p.contains('*') {
-            return Self::match_segments(&pattern.gts_id_segments, &self.gts_id_segments);
+        // If pattern is longer than candidate, no match
+        if pattern_segs.len() > candidate_segs.len() {
+            return false;
         }
 
-        // Wildcard case
-        if p.matches('*').count() > 1 || !p.ends_with('*') {
+        // Segments before the wildcard (or all of them) must match exactly, except
+        // for a single-token wildcard
+        let exact_len = pattern.wildcard_segment.unwrap_or(pattern_segs.len());
+        let exact_match = pattern_segs[..exact_len]
+            .iter()
+            .zip(candidate_segs)
+            .enumerate()
+            .all(|(i, (p_seg, c_seg))| {
+                let any_token = pattern
+                    .token_wildcard
+                    .filter(|(seg, _)| *seg == i)
+                    .map(|(_, token)| token);
+                Self::match_exact_segment(p_seg, c_seg, any_token, pattern.strict_minor)
+            });
+        if !exact_match {
             return false;
         }
 
-        Self::match_segments(&pattern.gts_id_segments, &self.gts_id_segments)
+        // Wildcard matches - accept anything after this point
+        pattern.wildcard_segment.is_none_or(|idx| {
+            Self::match_wildcard_segment(&pattern_segs[idx], &candidate_segs[idx])
+        })
     }
 
-    fn match_segments(pattern_segs: &[GtsIdSegment], candidate_segs: &[GtsIdSegment]) -> bool {
-        // If pattern is longer than candidate, no match
-        if pattern_segs.len() > candidate_segs.len() {
+    /// Non-wildcard segment - all fields must match exactly, except versions a
+    /// versionless type reference or a missing minor leaves open, and the name token
+    /// at `any_token` (0 = vendor .. 3 = type name) if given. With `strict_minor`, a
+    /// missing minor only matches a candidate minor of 0 or none.
+    fn match_exact_segment(
+        p_seg: &GtsIdSegment,
+        c_seg: &GtsIdSegment,
+        any_token: Option<usize>,
+        strict_minor: bool,
+    ) -> bool {
+        let p_names = [
+            &p_seg.vendor,
+            &p_seg.package,
+            &p_seg.namespace,
+            &p_seg.type_name,
+        ];
+        let c_names = [
+            &c_seg.vendor,
+            &c_seg.package,
+            &c_seg.namespace,
+            &c_seg.type_name,
+        ];
+        let names_match = p_names
+            .iter()
+            .zip(c_names)
+            .enumerate()
+            .all(|(i, (p_name, c_name))| any_token == Some(i) || *p_name == c_name);
+        if !names_match {
             return false;
         }
 
-        for (i, p_seg) in pattern_segs.iter().enumerate() {
-            let c_seg = &candidate_segs[i];
-
-            // If pattern segment is a wildcard, check non-wildcard fields first
-            if p_seg.is_wildcard {
-                if !p_seg.vendor.is_empty() && p_seg.vendor != c_seg.vendor {
-                    return false;
-                }
-                if !p_seg.package.is_empty() && p_seg.package != c_seg.package {
-                    return false;
-                }
-                if !p_seg.namespace.is_empty() && p_seg.namespace != c_seg.namespace {
-                    return false;
-                }
-                if !p_seg.type_name.is_empty() && p_seg.type_name != c_seg.type_name {
-                    return false;
-                }
-                if p_seg.ver_major != 0 && p_seg.ver_major != c_seg.ver_major {
-                    return false;
-                }
-                if let Some(p_minor) = p_seg.ver_minor {
-                    if Some(p_minor) != c_seg.ver_minor {
-                        return false;
-                    }
-                }
-                if p_seg.is_type && p_seg.is_type != c_seg.is_type {
-                    return false;
-                }
-                // Wildcard matches - accept anything after this point
-                return true;
-            }
-
-            // Non-wildcard segment - all fields must match exactly
-            if p_seg.vendor != c_seg.vendor {
-                return false;
-            }
-            if p_seg.package != c_seg.package {
-                return false;
-            }
-            if p_seg.namespace != c_seg.namespace {
-                return false;
-            }
-            if p_seg.type_name != c_seg.type_name {
-                return false;
-            }
-
-            // Check version matching
+        // Check version matching; a versionless type reference accepts any version
+        if !p_seg.is_versionless() {
             if p_seg.ver_major != c_seg.ver_major {
                 return false;
             }
 
             // Minor version: if pattern has no minor version, accept any minor in candidate
-            if let Some(p_minor) = p_seg.ver_minor {
-                if Some(p_minor) != c_seg.ver_minor {
-                    return false;
-                }
+            // unless it is strict
+            match p_seg.ver_minor {
+                Some(p_minor) if Some(p_minor) != c_seg.ver_minor => return false,
+                None if strict_minor && c_seg.ver_minor.unwrap_or(0) != 0 => return false,
+                _ => {}
             }
+        }
+
+        // Check is_type flag matches
+        p_seg.is_type == c_seg.is_type
+    }
 
-            // Check is_type flag matches
-            if p_seg.is_type != c_seg.is_type {
+    /// Wildcard segment - only the fields given before the `*` must match.
+    fn match_wildcard_segment(p_seg: &GtsIdSegment, c_seg: &GtsIdSegment) -> bool {
+        if !p_seg.vendor.is_empty() && p_seg.vendor != c_seg.vendor {
+            return false;
+        }
+        if !p_seg.package.is_empty() && p_seg.package != c_seg.package {
+            return false;
+        }
+        if !p_seg.namespace.is_empty() && p_seg.namespace != c_seg.namespace {
+            return false;
+        }
+        if !p_seg.type_name.is_empty() && p_seg.type_name != c_seg.type_name {
+            return false;
+        }
+        if p_seg.ver_major != 0 && p_seg.ver_major != c_seg.ver_major {
+            return false;
+        }
+        if let Some(p_minor) = p_seg.ver_minor {
+            if Some(p_minor) != c_seg.ver_minor {
                 return false;
             }
         }
-
-        true
+        !p_seg.is_type || c_seg.is_type
     }
 
-    /// Splits a GTS ID with an optional attribute path.
+    /// Splits a GTS ID with an optional attribute path at the first `@`, which cannot
+    /// occur in the ID itself, so a quoted key such as `@["a@b"]` stays in the path.
+    /// See [`crate::parse_attribute_path`] for the path grammar; the path can be
+    /// resolved against an instance with [`crate::resolve_attribute_path`].
     ///
     /// # Errors
     /// Returns `GtsError::InvalidId` if the path is empty after the `@` separator.
@@ -479,6 +1090,37 @@ impl fmt::Display for GtsID {
     }
 }
 
+/// Orders IDs segment by segment from the root, comparing vendor, package, namespace,
+/// type name, major version, minor version (absent before `0`) and then the type
+/// marker (instances before types). A chain sorts before longer chains it is a prefix
+/// of. Remaining ties, such as `x.core.events.event~` against `x.core.events.event.v0~`,
+/// are broken by the ID string, so the order is total and consistent with `==`.
+impl Ord for GtsID {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn key(seg: &GtsIdSegment) -> (&str, &str, &str, &str, u32, Option<u32>, bool) {
+            (
+                &seg.vendor,
+                &seg.package,
+                &seg.namespace,
+                &seg.type_name,
+                seg.ver_major,
+                seg.ver_minor,
+                seg.is_type,
+            )
+        }
+        self.segments_iter()
+            .map(key)
+            .cmp(other.segments_iter().map(key))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for GtsID {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FromStr for GtsID {
     type Err = GtsError;
 
@@ -487,17 +1129,149 @@ impl FromStr for GtsID {
     }
 }
 
-impl AsRef<str> for GtsID {
+impl TryFrom<&str> for GtsID {
+    type Error = GtsError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for GtsID {
+    type Error = GtsError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(&s)
+    }
+}
+
+/// Serializes as the plain ID string.
+impl Serialize for GtsID {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+/// Deserializes from an ID string, reporting parse failures as serde errors.
+impl<'de> Deserialize<'de> for GtsID {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::new(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl AsRef<str> for GtsID {
     fn as_ref(&self) -> &str {
         &self.id
     }
 }
 
+/// Builds a `GtsID` from its parts instead of a hand-assembled string.
+///
+/// Each `chain` call starts a new segment; every segment before the last is a type
+/// and gets the `~` marker. `build` formats the canonical ID and parses it with
+/// `GtsID::new`, so invalid tokens are reported as usual.
+///
+/// ```
+/// use gts::GtsIdBuilder;
+///
+/// let id = GtsIdBuilder::new("x", "core", "events", "event", 1)
+///     .chain("x", "app", "orders", "created", 1)
+///     .minor(0)
+///     .build()
+///     .unwrap();
+/// assert_eq!(id.id, "gts.x.core.events.event.v1~x.app.orders.created.v1.0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtsIdBuilder {
+    segments: Vec<String>,
+    is_type: bool,
+}
+
+impl GtsIdBuilder {
+    /// Starts an ID whose first segment is `vendor.package.namespace.type_name.v<major>`.
+    #[must_use]
+    pub fn new(vendor: &str, package: &str, namespace: &str, type_name: &str, major: u32) -> Self {
+        GtsIdBuilder {
+            segments: vec![Self::segment(vendor, package, namespace, type_name, major)],
+            is_type: false,
+        }
+    }
+
+    /// Sets the minor version of the current segment.
+    #[must_use]
+    pub fn minor(mut self, minor: u32) -> Self {
+        if let Some(seg) = self.segments.last_mut() {
+            seg.push('.');
+            seg.push_str(&minor.to_string());
+        }
+        self
+    }
+
+    /// Appends a segment; the current one becomes its parent type.
+    #[must_use]
+    pub fn chain(
+        mut self,
+        vendor: &str,
+        package: &str,
+        namespace: &str,
+        type_name: &str,
+        major: u32,
+    ) -> Self {
+        self.segments
+            .push(Self::segment(vendor, package, namespace, type_name, major));
+        self
+    }
+
+    /// Whether the last segment ends with the `~` type marker, making the ID a type ID.
+    #[must_use]
+    pub fn is_type(mut self, is_type: bool) -> Self {
+        self.is_type = is_type;
+        self
+    }
+
+    /// Formats and validates the ID.
+    ///
+    /// # Errors
+    /// Returns `GtsError` if a token is not a valid GTS token.
+    pub fn build(&self) -> Result<GtsID, GtsError> {
+        let marker = if self.is_type { "~" } else { "" };
+        GtsID::new(&format!("{GTS_PREFIX}{}{marker}", self.segments.join("~")))
+    }
+
+    fn segment(
+        vendor: &str,
+        package: &str,
+        namespace: &str,
+        type_name: &str,
+        major: u32,
+    ) -> String {
+        format!("{vendor}.{package}.{namespace}.{type_name}.v{major}")
+    }
+}
+
+/// Options for `GtsWildcard::new_with_options`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GtsWildcardOptions {
+    /// Read a pattern segment without a minor version as minor 0 only, instead of
+    /// any minor version. A versionless type reference still matches any version.
+    pub strict_minor: bool,
+}
+
 /// GTS Wildcard pattern
 #[derive(Debug, Clone, PartialEq)]
 pub struct GtsWildcard {
     pub id: String,
     pub gts_id_segments: Vec<GtsIdSegment>,
+    /// Optional attribute path selector following the `@` separator, e.g. `payload.*`
+    pub attr_path: Option<String>,
+    /// Index of the segment ending in `*`, computed once at construction
+    wildcard_segment: Option<usize>,
+    /// Segment index and token position of a mid-pattern `*` that stands for exactly
+    /// one of the vendor, package, namespace or type name tokens
+    token_wildcard: Option<(usize, usize)>,
+    /// See `GtsWildcardOptions::strict_minor`
+    strict_minor: bool,
 }
 
 impl GtsWildcard {
@@ -506,7 +1280,29 @@ impl GtsWildcard {
     /// # Errors
     /// Returns `GtsError::InvalidWildcard` if the pattern is invalid.
     pub fn new(pattern: &str) -> Result<Self, GtsError> {
-        let p = pattern.trim();
+        Self::new_with_options(pattern, &GtsWildcardOptions::default())
+    }
+
+    /// Creates a new GTS wildcard pattern with the given matching options.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidWildcard` if the pattern is invalid.
+    pub fn new_with_options(pattern: &str, options: &GtsWildcardOptions) -> Result<Self, GtsError> {
+        let (p, attr_path) =
+            GtsID::split_at_path(pattern.trim()).map_err(|e| GtsError::InvalidWildcard {
+                pattern: pattern.to_owned(),
+                cause: e.to_string(),
+            })?;
+        let p = p.as_str();
+
+        if let Some(ref path) = attr_path {
+            if !is_valid_attr_path(path, true) {
+                return Err(GtsError::InvalidWildcard {
+                    pattern: pattern.to_owned(),
+                    cause: format!("Invalid attribute path '{path}'"),
+                });
+            }
+        }
 
         if !p.starts_with(GTS_PREFIX) {
             return Err(GtsError::InvalidWildcard {
@@ -522,32 +1318,155 @@ impl GtsWildcard {
             });
         }
 
-        if p.contains('*') && !p.ends_with(".*") && !p.ends_with("~*") {
-            return Err(GtsError::InvalidWildcard {
-                pattern: pattern.to_owned(),
-                cause: "The wildcard '*' token is allowed only at the end of the pattern".to_owned(),
-            });
-        }
+        let token_wildcard = if p.contains('*') && !p.ends_with(".*") && !p.ends_with("~*") {
+            Some(
+                Self::find_token_wildcard(p).map_err(|cause| GtsError::InvalidWildcard {
+                    pattern: pattern.to_owned(),
+                    cause,
+                })?,
+            )
+        } else {
+            None
+        };
 
-        // Try to parse as GtsID
-        let gts_id = GtsID::new(p).map_err(|e| GtsError::InvalidWildcard {
+        // Try to parse as GtsID, with a single-token wildcard replaced by a valid token
+        let parsed = match token_wildcard {
+            Some(_) => GtsID::new(&p.replacen('*', TOKEN_PLACEHOLDER, 1)),
+            None => GtsID::new(p),
+        };
+        let mut gts_id = parsed.map_err(|e| GtsError::InvalidWildcard {
             pattern: pattern.to_owned(),
             cause: e.to_string(),
         })?;
 
+        if let Some((seg_idx, token_idx)) = token_wildcard {
+            let seg = &mut gts_id.gts_id_segments[seg_idx];
+            let mut tokens: Vec<String> = seg.segment.split('.').map(str::to_owned).collect();
+            tokens[token_idx] = tokens[token_idx].replacen(TOKEN_PLACEHOLDER, "*", 1);
+            seg.segment = tokens.join(".");
+            let name = match token_idx {
+                0 => &mut seg.vendor,
+                1 => &mut seg.package,
+                2 => &mut seg.namespace,
+                _ => &mut seg.type_name,
+            };
+            name.clear();
+            p.clone_into(&mut gts_id.id);
+        }
+
+        let wildcard_segment = gts_id
+            .gts_id_segments
+            .iter()
+            .position(|seg| seg.is_wildcard);
+
         Ok(GtsWildcard {
             id: gts_id.id,
             gts_id_segments: gts_id.gts_id_segments,
+            attr_path,
+            wildcard_segment,
+            token_wildcard,
+            strict_minor: options.strict_minor,
         })
     }
+
+    /// Locates a `*` that is not at the end of the pattern. It must be a whole token
+    /// in the vendor, package, namespace or type name position.
+    fn find_token_wildcard(pattern: &str) -> Result<(usize, usize), String> {
+        let remainder = &pattern[GTS_PREFIX.len()..];
+        for (seg_idx, part) in remainder.split('~').enumerate() {
+            if !part.contains('*') {
+                continue;
+            }
+            return match part.split('.').position(|token| token == "*") {
+                Some(token_idx) if token_idx < 4 => Ok((seg_idx, token_idx)),
+                _ => Err(
+                    "A wildcard '*' before the end of the pattern must replace a whole vendor, package, namespace or type name token"
+                        .to_owned(),
+                ),
+            };
+        }
+        Err("The wildcard '*' token is allowed only at the end of the pattern".to_owned())
+    }
+
+    /// Check if a GTS ID with an optional attribute path matches this pattern.
+    ///
+    /// Segments are matched as in [`GtsID::wildcard_match`]. If the pattern carries an
+    /// attribute path, the candidate path must equal it, or, when the pattern path ends
+    /// with `*`, start with the preceding path components. Paths are compared by
+    /// their parsed segments, so `a.b` and `a["b"]` are equal.
+    #[must_use]
+    pub fn matches_with_path(&self, id: &GtsID, attr_path: Option<&str>) -> bool {
+        if !id.wildcard_match(self) {
+            return false;
+        }
+
+        let Some(pattern_path) = self.attr_path.as_deref() else {
+            return true;
+        };
+        let Some(path) = attr_path else {
+            return false;
+        };
+        let Some(segments) = parse_attribute_path(path) else {
+            return false;
+        };
+
+        if pattern_path == "*" {
+            return true;
+        }
+        match pattern_path.strip_suffix(".*") {
+            Some(prefix) => parse_attribute_path(prefix).is_some_and(|prefix| {
+                segments.len() > prefix.len() && segments.starts_with(&prefix)
+            }),
+            None => parse_attribute_path(pattern_path).is_some_and(|pattern| pattern == segments),
+        }
+    }
+
+    /// Returns the IDs from `ids` that match this pattern, in iteration order.
+    ///
+    /// Matching is the same as [`GtsID::wildcard_match`].
+    #[must_use]
+    pub fn filter<'a>(&self, ids: impl IntoIterator<Item = &'a GtsID>) -> Vec<&'a GtsID> {
+        ids.into_iter()
+            .filter(|id| id.wildcard_match(self))
+            .collect()
+    }
+
+    /// Check if any ID in `ids` matches this pattern.
+    #[must_use]
+    pub fn matches_any<'a>(&self, ids: impl IntoIterator<Item = &'a GtsID>) -> bool {
+        ids.into_iter().any(|id| id.wildcard_match(self))
+    }
+
+    /// Creates a new GTS wildcard pattern after lowercasing it, so it can be built
+    /// from upper- or mixed-case input.
+    ///
+    /// # Errors
+    /// Returns `GtsError::InvalidWildcard` if the lowercased pattern is invalid.
+    pub fn new_case_insensitive(pattern: &str) -> Result<Self, GtsError> {
+        Self::new(&pattern.to_lowercase())
+    }
 }
 
 impl fmt::Display for GtsWildcard {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.id)
+        match &self.attr_path {
+            Some(path) => write!(f, "{}@{path}", self.id),
+            None => write!(f, "{}", self.id),
+        }
     }
 }
 
+/// Check that an attribute path is valid for `parse_attribute_path`. With
+/// `allow_wildcard`, a trailing `*` component is accepted.
+fn is_valid_attr_path(path: &str, allow_wildcard: bool) -> bool {
+    let path = match path.strip_suffix(".*") {
+        _ if allow_wildcard && path == "*" => return true,
+        Some(prefix) if allow_wildcard => prefix,
+        _ => path,
+    };
+    parse_attribute_path(path).is_some()
+}
+
 impl FromStr for GtsWildcard {
     type Err = GtsError;
 
@@ -556,17 +1475,497 @@ impl FromStr for GtsWildcard {
     }
 }
 
+impl TryFrom<&str> for GtsWildcard {
+    type Error = GtsError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for GtsWildcard {
+    type Error = GtsError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(&s)
+    }
+}
+
 impl AsRef<str> for GtsWildcard {
     fn as_ref(&self) -> &str {
         &self.id
     }
 }
 
+/// Outcome of a `WildcardRuleSet` rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// Ordered allow/deny wildcard rules evaluated with first-match-wins semantics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WildcardRuleSet {
+    /// Rules in evaluation order.
+    pub rules: Vec<(GtsWildcard, Effect)>,
+    /// Effect returned when no rule matches.
+    pub default: Effect,
+}
+
+impl WildcardRuleSet {
+    /// Creates an empty rule set that evaluates every ID to `default`.
+    #[must_use]
+    pub fn new(default: Effect) -> Self {
+        WildcardRuleSet {
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    /// Appends a rule, evaluated after the ones already added.
+    #[must_use]
+    pub fn with_rule(mut self, pattern: GtsWildcard, effect: Effect) -> Self {
+        self.rules.push((pattern, effect));
+        self
+    }
+
+    /// Returns the effect of the first rule whose pattern matches `id`, as with
+    /// [`GtsID::wildcard_match`], or the default when none does.
+    #[must_use]
+    pub fn evaluate(&self, id: &GtsID) -> Effect {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| id.wildcard_match(pattern))
+            .map_or(self.default, |(_, effect)| *effect)
+    }
+}
+
+/// Boolean combination of wildcard patterns, such as everything under a prefix except
+/// one sub-namespace.
+///
+/// Build expressions with `and`, `or` and `!`, or parse the compact syntax where `&`
+/// binds tighter than `|`, `!` negates and parentheses group:
+///
+/// ```
+/// use gts::{GtsID, GtsWildcardExpr};
+///
+/// let expr: GtsWildcardExpr = "gts.x.core.* & !gts.x.core.audit.*".parse().unwrap();
+/// assert!(expr.matches(&GtsID::new("gts.x.core.events.event.v1~").unwrap()));
+/// assert!(!expr.matches(&GtsID::new("gts.x.core.audit.entry.v1~").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum GtsWildcardExpr {
+    Pattern(GtsWildcard),
+    /// Matches when every operand matches; an empty `And` matches everything.
+    And(Vec<GtsWildcardExpr>),
+    /// Matches when any operand matches; an empty `Or` matches nothing.
+    Or(Vec<GtsWildcardExpr>),
+    Not(Box<GtsWildcardExpr>),
+}
+
+impl GtsWildcardExpr {
+    /// Combines with `other` so both must match, extending `self` if it is an `And`.
+    #[must_use]
+    pub fn and(self, other: impl Into<GtsWildcardExpr>) -> Self {
+        match self {
+            GtsWildcardExpr::And(mut operands) => {
+                operands.push(other.into());
+                GtsWildcardExpr::And(operands)
+            }
+            expr => GtsWildcardExpr::And(vec![expr, other.into()]),
+        }
+    }
+
+    /// Combines with `other` so either may match, extending `self` if it is an `Or`.
+    #[must_use]
+    pub fn or(self, other: impl Into<GtsWildcardExpr>) -> Self {
+        match self {
+            GtsWildcardExpr::Or(mut operands) => {
+                operands.push(other.into());
+                GtsWildcardExpr::Or(operands)
+            }
+            expr => GtsWildcardExpr::Or(vec![expr, other.into()]),
+        }
+    }
+
+    /// Evaluates the expression, matching each pattern with [`GtsID::wildcard_match`].
+    #[must_use]
+    pub fn matches(&self, id: &GtsID) -> bool {
+        match self {
+            GtsWildcardExpr::Pattern(pattern) => id.wildcard_match(pattern),
+            GtsWildcardExpr::And(operands) => operands.iter().all(|expr| expr.matches(id)),
+            GtsWildcardExpr::Or(operands) => operands.iter().any(|expr| expr.matches(id)),
+            GtsWildcardExpr::Not(expr) => !expr.matches(id),
+        }
+    }
+}
+
+impl From<GtsWildcard> for GtsWildcardExpr {
+    fn from(pattern: GtsWildcard) -> Self {
+        GtsWildcardExpr::Pattern(pattern)
+    }
+}
+
+impl std::ops::Not for GtsWildcardExpr {
+    type Output = GtsWildcardExpr;
+
+    fn not(self) -> Self::Output {
+        GtsWildcardExpr::Not(Box::new(self))
+    }
+}
+
+impl FromStr for GtsWildcardExpr {
+    type Err = GtsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = WildcardExprParser { input: s, pos: 0 };
+        let expr = parser.or_expr()?;
+        parser.skip_whitespace();
+        if parser.pos < s.len() {
+            return Err(parser.error(&format!("Unexpected '{}'", &s[parser.pos..])));
+        }
+        Ok(expr)
+    }
+}
+
+/// Recursive descent parser for the `GtsWildcardExpr` syntax.
+struct WildcardExprParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl WildcardExprParser<'_> {
+    fn error(&self, cause: &str) -> GtsError {
+        GtsError::InvalidWildcard {
+            pattern: self.input.to_owned(),
+            cause: cause.to_owned(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `op` if it is the next non-whitespace character.
+    fn eat(&mut self, op: char) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(op) {
+            self.pos += op.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or_expr(&mut self) -> Result<GtsWildcardExpr, GtsError> {
+        let mut expr = self.and_expr()?;
+        while self.eat('|') {
+            expr = expr.or(self.and_expr()?);
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<GtsWildcardExpr, GtsError> {
+        let mut expr = self.unary()?;
+        while self.eat('&') {
+            expr = expr.and(self.unary()?);
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<GtsWildcardExpr, GtsError> {
+        if self.eat('!') {
+            return Ok(!self.unary()?);
+        }
+        if self.eat('(') {
+            let expr = self.or_expr()?;
+            if !self.eat(')') {
+                return Err(self.error("Missing ')'"));
+            }
+            return Ok(expr);
+        }
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "&|!()".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("Expected a wildcard pattern"));
+        }
+        self.pos += len;
+        Ok(GtsWildcardExpr::Pattern(GtsWildcard::new(&rest[..len])?))
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_gts_id_to_type_wildcard() {
+        let id = GtsID::new("gts.x.core.events.event.v1.2").expect("test");
+        let pattern = id.to_type_wildcard().expect("test");
+        assert_eq!(pattern.id, "gts.x.core.events.event.v1");
+
+        let matches = |candidate: &str| {
+            GtsID::new(candidate)
+                .expect("test")
+                .wildcard_match(&pattern)
+        };
+        assert!(matches("gts.x.core.events.event.v1.0"));
+        assert!(matches("gts.x.core.events.event.v1.7"));
+        assert!(!matches("gts.x.core.events.event.v2.0"));
+        assert!(!matches("gts.x.core.events.other.v1.2"));
+        assert!(!matches("gts.x.core.events.event.v1.2~"));
+
+        let schema = GtsID::new("gts.x.core.events.type.v1~x.app._.created.v1.3~").expect("test");
+        let pattern = schema.to_type_wildcard().expect("test");
+        assert_eq!(pattern.id, "gts.x.core.events.type.v1~x.app._.created.v1~");
+        let matches = |candidate: &str| {
+            GtsID::new(candidate)
+                .expect("test")
+                .wildcard_match(&pattern)
+        };
+        assert!(matches("gts.x.core.events.type.v1~x.app._.created.v1.0~"));
+        assert!(!matches("gts.x.core.events.type.v1~x.app._.deleted.v1.0~"));
+    }
+
+    #[test]
+    fn test_gts_id_to_prefix_wildcard() {
+        let id = GtsID::new("gts.x.core.events.type.v1~x.app._.created.v1.3").expect("test");
+
+        assert_eq!(id.to_prefix_wildcard(0).expect("test").id, "gts.*");
+        assert_eq!(id.to_prefix_wildcard(2).expect("test").id, "gts.x.core.*");
+        assert_eq!(
+            id.to_prefix_wildcard(5).expect("test").id,
+            "gts.x.core.events.type.v1~*"
+        );
+
+        let pattern = id.to_prefix_wildcard(3).expect("test");
+        let matches = |candidate: &str| {
+            GtsID::new(candidate)
+                .expect("test")
+                .wildcard_match(&pattern)
+        };
+        assert!(matches(&id.id));
+        assert!(matches("gts.x.core.events.event.v2.0"));
+        assert!(!matches("gts.x.other.events.event.v1.0"));
+
+        // An instance ID has no separator after its last token
+        assert!(id.to_prefix_wildcard(11).is_err());
+    }
+
+    #[test]
+    fn test_gts_id_segment_accessors_single_segment() {
+        let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+
+        assert_eq!(id.segments_iter().count(), 1);
+        assert_eq!(id.root_segment(), id.last_segment());
+        assert_eq!(id.last_segment().expect("test").ver_major, 1);
+        assert_eq!(id.type_identity(), Some(("x", "core", "events", "event")));
+    }
+
+    #[test]
+    fn test_gts_id_segment_accessors_multi_segment() {
+        let id =
+            GtsID::new("gts.x.core.events.type.v1~vendor.app._.custom_event.v1.2").expect("test");
+
+        let names: Vec<&str> = id.segments_iter().map(|s| s.type_name.as_str()).collect();
+        assert_eq!(names, vec!["type", "custom_event"]);
+        assert_eq!(id.root_segment().expect("test").vendor, "x");
+        assert_eq!(id.last_segment().expect("test").ver_minor, Some(2));
+        assert_eq!(
+            id.type_identity(),
+            Some(("vendor", "app", "_", "custom_event"))
+        );
+    }
+
+    #[test]
+    fn test_gts_id_indexed_segment_accessors() {
+        let id =
+            GtsID::new("gts.x.core.events.type.v1~vendor.app._.custom_event.v1.2").expect("test");
+        assert_eq!(id.segment_count(), 2);
+        assert_eq!(id.segment(0).expect("test").type_name, "type");
+        assert_eq!(id.segment(1).expect("test").type_name, "custom_event");
+        assert!(id.segment(2).is_none());
+        assert_eq!(id.nth_from_end(0).expect("test").type_name, "custom_event");
+        assert_eq!(id.nth_from_end(1).expect("test").type_name, "type");
+        assert!(id.nth_from_end(2).is_none());
+        assert!(id.nth_from_end(usize::MAX).is_none());
+
+        let empty = GtsID {
+            id: String::new(),
+            gts_id_segments: Vec::new(),
+        };
+        assert_eq!(empty.segment_count(), 0);
+        assert!(empty.segment(0).is_none());
+        assert!(empty.nth_from_end(0).is_none());
+        assert!(empty.root_segment().is_none());
+        assert!(empty.last_segment().is_none());
+        assert!(empty.get_type_id().is_none());
+    }
+
+    #[test]
+    fn test_gts_id_new_lenient() {
+        assert!(GtsID::new("gts.X.Core.Events.Event.v1~").is_err());
+
+        let (id, was_normalized) = GtsID::new_lenient("gts.X.Core.Events.Event.v1~").expect("test");
+        assert!(was_normalized);
+        assert_eq!(id.id, "gts.x.core.events.event.v1~");
+        assert_eq!(id.to_string().parse::<GtsID>().expect("test"), id);
+
+        let (id, was_normalized) = GtsID::new_lenient("gts.x.core.events.event.v1~").expect("test");
+        assert!(!was_normalized);
+        assert_eq!(id.id, "gts.x.core.events.event.v1~");
+
+        assert!(GtsID::new_lenient("GTS.x-core.events.event.v1~").is_err());
+    }
+
+    #[test]
+    fn test_wildcard_rule_set_first_match_wins() {
+        let rules = WildcardRuleSet::new(Effect::Deny)
+            .with_rule(
+                GtsWildcard::new("gts.x.core.events.audit.*").expect("test"),
+                Effect::Deny,
+            )
+            .with_rule(
+                GtsWildcard::new("gts.x.core.*").expect("test"),
+                Effect::Allow,
+            )
+            .with_rule(
+                GtsWildcard::new("gts.x.core.events.event.v1~").expect("test"),
+                Effect::Deny,
+            );
+        let evaluate = |id: &str| rules.evaluate(&GtsID::new(id).expect("test"));
+
+        // The earlier deny shadows the broader allow
+        assert_eq!(evaluate("gts.x.core.events.audit.v1~"), Effect::Deny);
+        // The allow comes before the narrower deny, so it wins
+        assert_eq!(evaluate("gts.x.core.events.event.v1~"), Effect::Allow);
+        assert_eq!(evaluate("gts.x.core.users.user.v2~"), Effect::Allow);
+        // No rule matches
+        assert_eq!(evaluate("gts.y.billing.invoices.invoice.v1~"), Effect::Deny);
+
+        let open = WildcardRuleSet::new(Effect::Allow);
+        assert_eq!(
+            open.evaluate(&GtsID::new("gts.y.billing.invoices.invoice.v1~").expect("test")),
+            Effect::Allow
+        );
+    }
+
+    #[test]
+    fn test_gts_wildcard_expr() {
+        let wildcard = |p: &str| GtsWildcardExpr::from(GtsWildcard::new(p).expect("test"));
+        let ids: Vec<GtsID> = [
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.events.audit.v1~",
+            "gts.x.core.users.user.v2~",
+            "gts.y.billing.invoices.invoice.v1~",
+            "gts.z.misc.things.thing.v1~",
+        ]
+        .iter()
+        .map(|id| GtsID::new(id).expect("test"))
+        .collect();
+        let matching = |expr: &GtsWildcardExpr| -> Vec<&str> {
+            ids.iter()
+                .filter(|id| expr.matches(id))
+                .map(|id| id.id.as_str())
+                .collect()
+        };
+
+        // A and not B
+        let except_audit = wildcard("gts.x.core.*").and(!wildcard("gts.x.core.events.audit.*"));
+        assert_eq!(
+            matching(&except_audit),
+            vec!["gts.x.core.events.event.v1~", "gts.x.core.users.user.v2~"]
+        );
+
+        // A or B
+        let core_users_or_billing = wildcard("gts.x.core.users.*").or(wildcard("gts.y.billing.*"));
+        assert_eq!(
+            matching(&core_users_or_billing),
+            vec![
+                "gts.x.core.users.user.v2~",
+                "gts.y.billing.invoices.invoice.v1~"
+            ]
+        );
+
+        assert_eq!(matching(&GtsWildcardExpr::And(Vec::new())).len(), ids.len());
+        assert!(matching(&GtsWildcardExpr::Or(Vec::new())).is_empty());
+
+        // The compact syntax builds the same expressions; & binds tighter than |
+        let parsed: GtsWildcardExpr = "gts.x.core.* & !gts.x.core.events.audit.*"
+            .parse()
+            .expect("test");
+        assert_eq!(parsed, except_audit);
+        let parsed: GtsWildcardExpr = "gts.x.core.users.* | gts.y.billing.*"
+            .parse()
+            .expect("test");
+        assert_eq!(parsed, core_users_or_billing);
+        let parsed: GtsWildcardExpr =
+            "gts.z.* | gts.x.core.* & !(gts.x.core.events.* | gts.x.core.users.*)"
+                .parse()
+                .expect("test");
+        assert_eq!(matching(&parsed), vec!["gts.z.misc.things.thing.v1~"]);
+
+        for invalid in [
+            "",
+            "gts.x.core.* &",
+            "(gts.x.core.*",
+            "gts.x.core.*)",
+            "!",
+            "gts.X.core.*",
+        ] {
+            let err = invalid.parse::<GtsWildcardExpr>().unwrap_err();
+            assert!(matches!(err, GtsError::InvalidWildcard { .. }), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_gts_wildcard_strict_minor() {
+        let candidate =
+            GtsID::new("gts.x.core.events.event.v1~x.app.orders.created.v1.2").expect("test");
+        let minor_zero =
+            GtsID::new("gts.x.core.events.event.v1~x.app.orders.created.v1.0").expect("test");
+        let pattern = "gts.x.core.events.event.v1~x.app.orders.created.v1";
+        let strict = GtsWildcardOptions { strict_minor: true };
+
+        let lenient = GtsWildcard::new(pattern).expect("test");
+        assert!(candidate.wildcard_match(&lenient));
+        assert!(minor_zero.wildcard_match(&lenient));
+
+        let strict_pattern = GtsWildcard::new_with_options(pattern, &strict).expect("test");
+        assert!(!candidate.wildcard_match(&strict_pattern));
+        assert!(minor_zero.wildcard_match(&strict_pattern));
+
+        // An explicit minor and a trailing wildcard are unaffected
+        let explicit =
+            GtsWildcard::new_with_options(&format!("{pattern}.2"), &strict).expect("test");
+        assert!(candidate.wildcard_match(&explicit));
+        let prefix = GtsWildcard::new_with_options("gts.x.core.events.event.v1~x.app.*", &strict)
+            .expect("test");
+        assert!(candidate.wildcard_match(&prefix));
+
+        // Earlier segments without a minor are strict too
+        let parent =
+            GtsID::new("gts.x.core.events.event.v1.1~x.app.orders.created.v1.0").expect("test");
+        assert!(parent.wildcard_match(&lenient));
+        assert!(!parent.wildcard_match(&strict_pattern));
+    }
+
+    #[test]
+    fn test_gts_wildcard_new_case_insensitive() {
+        assert!(GtsWildcard::new("gts.X.Core.*").is_err());
+
+        let pattern = GtsWildcard::new_case_insensitive("GTS.X.Core.*").expect("test");
+        assert_eq!(pattern.id, "gts.x.core.*");
+
+        let (id, _) = GtsID::new_lenient("gts.x.CORE.events.event.v1~").expect("test");
+        assert!(id.wildcard_match(&pattern));
+    }
+
     #[test]
     fn test_gts_id_valid() {
         let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
@@ -628,6 +2027,33 @@ mod tests {
         assert!(!id.wildcard_match(&pattern));
     }
 
+    #[test]
+    fn test_gts_wildcard_filter_and_matches_any() {
+        let pattern = GtsWildcard::new("gts.x.core.events.*").expect("test");
+        let ids: Vec<GtsID> = [
+            "gts.x.core.events.event.v1~",
+            "gts.y.core.events.event.v1~",
+            "gts.x.core.events.topic.v2~",
+            "gts.x.core.audit.entry.v1~",
+        ]
+        .iter()
+        .map(|id| GtsID::new(id).expect("test"))
+        .collect();
+
+        let matched: Vec<&str> = pattern
+            .filter(&ids)
+            .iter()
+            .map(|id| id.id.as_str())
+            .collect();
+        assert_eq!(
+            matched,
+            vec!["gts.x.core.events.event.v1~", "gts.x.core.events.topic.v2~"]
+        );
+        assert!(pattern.matches_any(&ids));
+        assert!(!pattern.matches_any(&ids[1..2]));
+        assert!(pattern.filter(&[]).is_empty());
+    }
+
     #[test]
     fn test_gts_wildcard_type_suffix() {
         // Wildcard after ~ should match type IDs
@@ -636,6 +2062,36 @@ mod tests {
         assert!(id.wildcard_match(&pattern));
     }
 
+    #[test]
+    fn test_compare_version_major_bump() {
+        let v1 = GtsID::new("gts.x.core.events.event.v1.9~").expect("test");
+        let v2 = GtsID::new("gts.x.core.events.event.v2.0~").expect("test");
+        assert_eq!(v1.compare_version(&v2), Some(Ordering::Less));
+        assert_eq!(v2.compare_version(&v1), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_compare_version_minor_bump() {
+        let v1_0 = GtsID::new("gts.x.core.events.event.v1.0").expect("test");
+        let v1_1 = GtsID::new("gts.x.core.events.event.v1.1~").expect("test");
+        assert_eq!(v1_0.compare_version(&v1_1), Some(Ordering::Less));
+        assert_eq!(v1_1.compare_version(&v1_0), Some(Ordering::Greater));
+        assert_eq!(v1_0.compare_version(&v1_0), Some(Ordering::Equal));
+
+        // Only one side has a minor version
+        let v1 = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert_eq!(v1.compare_version(&v1_1), None);
+    }
+
+    #[test]
+    fn test_compare_version_different_types() {
+        let event = GtsID::new("gts.x.core.events.event.v1.0~").expect("test");
+        let other = GtsID::new("gts.x.core.events.other.v1.1~").expect("test");
+        let vendor = GtsID::new("gts.y.core.events.event.v1.1~").expect("test");
+        assert_eq!(event.compare_version(&other), None);
+        assert_eq!(event.compare_version(&vendor), None);
+    }
+
     #[test]
     fn test_uuid_generation() {
         let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
@@ -646,6 +2102,27 @@ mod tests {
         assert!(!uuid1.to_string().is_empty());
     }
 
+    #[test]
+    fn test_uuid_with_namespace() {
+        assert_eq!(
+            GTS_UUID_NAMESPACE,
+            Uuid::new_v5(&Uuid::NAMESPACE_URL, b"gts")
+        );
+
+        let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        let custom = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://example.com/ids");
+        assert_eq!(
+            id.to_uuid(),
+            Uuid::new_v5(&GTS_UUID_NAMESPACE, id.id.as_bytes())
+        );
+        assert_eq!(id.to_uuid(), id.to_uuid_with_namespace(&GTS_UUID_NAMESPACE));
+        assert_ne!(id.to_uuid(), id.to_uuid_with_namespace(&custom));
+        assert_eq!(
+            id.to_uuid_with_namespace(&custom),
+            id.to_uuid_with_namespace(&custom)
+        );
+    }
+
     #[test]
     fn test_uuid_different_ids() {
         let id1 = GtsID::new("gts.x.core.events.event.v1~").expect("test");
@@ -673,7 +2150,7 @@ mod tests {
         let (gts, path) =
             GtsID::split_at_path("gts.x.core.events.event.v1~@field.subfield").expect("test");
         assert_eq!(gts, "gts.x.core.events.event.v1~");
-        assert_eq!(path, Some("field.subfield".to_string()));
+        assert_eq!(path, Some("field.subfield".to_owned()));
     }
 
     #[test]
@@ -791,7 +2268,7 @@ mod tests {
         let (gts, path) =
             GtsID::split_at_path("gts.x.core.events.event.v1~@field@subfield").expect("test");
         assert_eq!(gts, "gts.x.core.events.event.v1~");
-        assert_eq!(path, Some("field@subfield".to_string()));
+        assert_eq!(path, Some("field@subfield".to_owned()));
     }
 
     #[test]
@@ -821,9 +2298,11 @@ mod tests {
 
     #[test]
     fn test_gts_wildcard_only_at_end() {
-        // Wildcard in middle should fail
-        let result1 = GtsWildcard::new("gts.*.core.events.event.v1~");
+        // Wildcard in the middle of a token or in a version position should fail
+        let result1 = GtsWildcard::new("gts.x.core.ev*.event.v1~");
         assert!(result1.is_err());
+        let result2 = GtsWildcard::new("gts.x.core.events.event.*.0~");
+        assert!(result2.is_err());
 
         // Wildcard at end should work
         let pattern2 = GtsWildcard::new("gts.x.core.events.*").expect("test");
@@ -831,6 +2310,92 @@ mod tests {
         assert!(id2.wildcard_match(&pattern2));
     }
 
+    #[test]
+    fn test_gts_wildcard_mid_pattern_single_token() {
+        let pattern = GtsWildcard::new("gts.x.core.*.event.v1~").expect("test");
+        assert_eq!(pattern.id, "gts.x.core.*.event.v1~");
+        assert_eq!(pattern.to_string(), "gts.x.core.*.event.v1~");
+
+        let matches = |id: &str| GtsID::new(id).expect("test").wildcard_match(&pattern);
+        assert!(matches("gts.x.core.events.event.v1~"));
+        assert!(matches("gts.x.core.audit.event.v1.2~"));
+        assert!(matches("gts.x.core.audit.event.v1~x.app.ns.login.v1~"));
+        assert!(!matches("gts.y.core.events.event.v1~"));
+        assert!(!matches("gts.x.core.events.other.v1~"));
+        assert!(!matches("gts.x.core.events.event.v2~"));
+        assert!(!matches("gts.x.core.events.event.v1.0"));
+
+        let vendor = GtsWildcard::new("gts.*.core.events.event.v1~").expect("test");
+        assert!(GtsID::new("gts.acme.core.events.event.v1~")
+            .expect("test")
+            .wildcard_match(&vendor));
+
+        // The single-token wildcard can apply to a later chained segment too
+        let chained =
+            GtsWildcard::new("gts.x.core.events.event.v1~x.app.*.login.v1~").expect("test");
+        assert!(
+            GtsID::new("gts.x.core.events.event.v1~x.app.auth.login.v1~")
+                .expect("test")
+                .wildcard_match(&chained)
+        );
+        assert!(
+            !GtsID::new("gts.x.core.events.event.v1~x.app.auth.logout.v1~")
+                .expect("test")
+                .wildcard_match(&chained)
+        );
+
+        // Only one '*' is allowed, whether mid-pattern or trailing
+        assert!(GtsWildcard::new("gts.x.core.*.event.*").is_err());
+    }
+
+    #[test]
+    fn test_gts_id_normalize() {
+        let canonical = "gts.x.core.events.event.v1~x.app.ns.login.v1.0";
+        assert_eq!(GtsID::normalize(canonical).expect("test"), canonical);
+
+        let spaced = "  gts.x.core.events.event.v1~ x.app.ns.login.v1.0 ";
+        let normalized = GtsID::normalize(spaced).expect("test");
+        assert_eq!(normalized, canonical);
+        assert_eq!(
+            GtsID::new(&normalized).expect("test").to_uuid(),
+            GtsID::new(canonical).expect("test").to_uuid()
+        );
+
+        assert_eq!(
+            GtsID::normalize("gts.x.core.events.event~").expect("test"),
+            "gts.x.core.events.event~"
+        );
+        assert!(GtsID::normalize("gts.X.core.events.event.v1~").is_err());
+    }
+
+    #[test]
+    fn test_gts_id_ordering() {
+        let expected = [
+            "gts.a.core.events.event.v1~",
+            "gts.x.app.events.event.v1~",
+            "gts.x.core.events.alpha.v2~",
+            "gts.x.core.events.event.v1",
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.events.event.v1~x.app.ns.a.v1.0",
+            "gts.x.core.events.event.v1~x.app.ns.b.v1.0",
+            "gts.x.core.events.event.v1.0~",
+            "gts.x.core.events.event.v1.2~",
+            "gts.x.core.events.event.v1.10~",
+            "gts.x.core.events.event.v2~",
+        ];
+        let mut ids: Vec<GtsID> = [5, 10, 0, 8, 3, 7, 1, 9, 4, 2, 6]
+            .iter()
+            .map(|&i| GtsID::new(expected[i]).expect("test"))
+            .collect();
+        ids.sort();
+        let sorted: Vec<&str> = ids.iter().map(|id| id.id.as_str()).collect();
+        assert_eq!(sorted, expected);
+
+        let a = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+        assert!(GtsID::new("gts.x.core.events.event~").expect("test") < a);
+    }
+
     #[test]
     fn test_gts_id_version_without_minor() {
         let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
@@ -838,6 +2403,33 @@ mod tests {
         assert_eq!(id.gts_id_segments[0].ver_minor, None);
     }
 
+    #[test]
+    fn test_gts_id_versionless_type_reference() {
+        let id = GtsID::new("gts.x.core.events.event~").expect("test");
+        assert!(id.is_type());
+        let seg = &id.gts_id_segments[0];
+        assert!(seg.is_versionless());
+        assert_eq!(seg.type_name, "event");
+        assert_eq!(seg.ver_major, 0);
+        assert_eq!(seg.ver_minor, None);
+
+        // Versioned segments and instances are not versionless
+        let versioned = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert!(!versioned.gts_id_segments[0].is_versionless());
+        assert!(GtsID::new("gts.x.core.events.event").is_err());
+    }
+
+    #[test]
+    fn test_gts_wildcard_versionless_matches_any_version() {
+        let pattern = GtsWildcard::new("gts.x.core.events.event~").expect("test");
+        let v1 = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        let v2 = GtsID::new("gts.x.core.events.event.v2.3~").expect("test");
+        let other = GtsID::new("gts.x.core.events.other.v1~").expect("test");
+        assert!(v1.wildcard_match(&pattern));
+        assert!(v2.wildcard_match(&pattern));
+        assert!(!other.wildcard_match(&pattern));
+    }
+
     #[test]
     fn test_gts_id_version_with_large_numbers() {
         let id = GtsID::new("gts.x.core.events.event.v99.999~").expect("test");
@@ -869,13 +2461,62 @@ mod tests {
     #[test]
     fn test_gts_id_display_trait() {
         let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
-        assert_eq!(format!("{}", id), "gts.x.core.events.event.v1~");
+        assert_eq!(format!("{id}"), "gts.x.core.events.event.v1~");
     }
 
     #[test]
     fn test_gts_id_from_str_trait() {
         let id: GtsID = "gts.x.core.events.event.v1~".parse().expect("test");
         assert_eq!(id.id, "gts.x.core.events.event.v1~");
+        assert_eq!(
+            GtsID::try_from("gts.x.core.events.event.v1~").expect("test"),
+            id
+        );
+        assert_eq!(
+            GtsID::try_from("gts.x.core.events.event.v1~".to_owned()).expect("test"),
+            id
+        );
+
+        for invalid in [
+            "gts.x.core.events.event",
+            "x.core.events.event.v1~",
+            "gts.X.core.events.event.v1~",
+        ] {
+            assert!(invalid.parse::<GtsID>().is_err(), "{invalid}");
+            assert!(GtsID::try_from(invalid).is_err(), "{invalid}");
+            assert!(GtsID::try_from(invalid.to_owned()).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_gts_id_serde_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Holder {
+            id: GtsID,
+        }
+
+        let id = GtsID::new("gts.x.core.events.type.v1~x.app.orders.created.v1.0").expect("test");
+        let value = serde_json::to_value(&id).expect("test");
+        assert_eq!(value, serde_json::json!(id.id));
+        assert_eq!(serde_json::from_value::<GtsID>(value).expect("test"), id);
+
+        let holder: Holder =
+            serde_json::from_str(r#"{"id":"gts.x.core.events.event.v1~"}"#).expect("test");
+        assert_eq!(holder.id.id, "gts.x.core.events.event.v1~");
+        assert_eq!(holder.id.gts_id_segments.len(), 1);
+        assert_eq!(
+            serde_json::to_string(&holder).expect("test"),
+            r#"{"id":"gts.x.core.events.event.v1~"}"#
+        );
+
+        let err = serde_json::from_str::<Holder>(r#"{"id":"gts.X.core.events.event.v1~"}"#)
+            .err()
+            .expect("invalid ID must not deserialize");
+        assert!(
+            err.to_string().contains("gts.X.core.events.event.v1~"),
+            "{err}"
+        );
+        assert!(serde_json::from_str::<GtsID>("42").is_err());
     }
 
     #[test]
@@ -888,13 +2529,31 @@ mod tests {
     #[test]
     fn test_gts_wildcard_display_trait() {
         let pattern = GtsWildcard::new("gts.x.core.events.*").expect("test");
-        assert_eq!(format!("{}", pattern), "gts.x.core.events.*");
+        assert_eq!(format!("{pattern}"), "gts.x.core.events.*");
     }
 
     #[test]
     fn test_gts_wildcard_from_str_trait() {
         let pattern: GtsWildcard = "gts.x.core.events.*".parse().expect("test");
         assert_eq!(pattern.id, "gts.x.core.events.*");
+        assert_eq!(
+            GtsWildcard::try_from("gts.x.core.events.*").expect("test"),
+            pattern
+        );
+        assert_eq!(
+            GtsWildcard::try_from("gts.x.core.events.*".to_owned()).expect("test"),
+            pattern
+        );
+
+        // Wildcard-specific validation still runs
+        for invalid in ["gts.x.*.events.*", "gts.x.core.*@payload..body", "x.core.*"] {
+            assert!(invalid.parse::<GtsWildcard>().is_err(), "{invalid}");
+            assert!(GtsWildcard::try_from(invalid).is_err(), "{invalid}");
+            assert!(
+                GtsWildcard::try_from(invalid.to_owned()).is_err(),
+                "{invalid}"
+            );
+        }
     }
 
     #[test]
@@ -903,4 +2562,561 @@ mod tests {
         let s: &str = pattern.as_ref();
         assert_eq!(s, "gts.x.core.events.*");
     }
+
+    #[test]
+    fn test_gts_wildcard_matches_with_attr_path() {
+        let pattern = GtsWildcard::new("gts.x.core.events.event.v1~@payload.*").expect("test");
+        assert_eq!(pattern.id, "gts.x.core.events.event.v1~");
+        assert_eq!(pattern.attr_path.as_deref(), Some("payload.*"));
+        assert_eq!(pattern.to_string(), "gts.x.core.events.event.v1~@payload.*");
+
+        let id = GtsID::new("gts.x.core.events.event.v1.2~").expect("test");
+        assert!(pattern.matches_with_path(&id, Some("payload.body")));
+        assert!(pattern.matches_with_path(&id, Some("payload.items[0].sku")));
+        assert!(!pattern.matches_with_path(&id, Some("payload")));
+        assert!(!pattern.matches_with_path(&id, Some("payloads.body")));
+        assert!(!pattern.matches_with_path(&id, Some("meta.body")));
+        assert!(!pattern.matches_with_path(&id, None));
+
+        let other = GtsID::new("gts.x.core.events.other.v1~").expect("test");
+        assert!(!pattern.matches_with_path(&other, Some("payload.body")));
+    }
+
+    #[test]
+    fn test_gts_wildcard_exact_attr_path() {
+        let pattern = GtsWildcard::new("gts.x.core.*@order.items[1].sku").expect("test");
+        let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert!(pattern.matches_with_path(&id, Some("order.items[1].sku")));
+        assert!(!pattern.matches_with_path(&id, Some("order.items[2].sku")));
+
+        let any = GtsWildcard::new("gts.x.core.*").expect("test");
+        assert!(any.matches_with_path(&id, Some("order.items[1].sku")));
+        assert!(any.matches_with_path(&id, None));
+    }
+
+    #[test]
+    fn test_attr_path_quoted_keys() {
+        let (gts, path) =
+            GtsID::split_at_path(r#"gts.x.core.events.event.v1~@meta["weird.key"]["a@b"]"#)
+                .expect("test");
+        assert_eq!(gts, "gts.x.core.events.event.v1~");
+        assert_eq!(path.as_deref(), Some(r#"meta["weird.key"]["a@b"]"#));
+
+        let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        let exact = GtsWildcard::new(r#"gts.x.core.*@meta["weird.key"]"#).expect("test");
+        assert!(exact.matches_with_path(&id, Some(r#"meta["weird.key"]"#)));
+        assert!(!exact.matches_with_path(&id, Some("meta.weird.key")));
+
+        let prefix = GtsWildcard::new(r#"gts.x.core.*@["a@b"].*"#).expect("test");
+        assert!(prefix.matches_with_path(&id, Some(r#"["a@b"].c"#)));
+        assert!(prefix.matches_with_path(&id, Some(r#"["a@b"][0]"#)));
+        assert!(!prefix.matches_with_path(&id, Some(r#"["a@b"]"#)));
+        assert!(!prefix.matches_with_path(&id, Some("a@b.c")));
+
+        // Bare and quoted forms of the same key are equal
+        let payload = GtsWildcard::new("gts.x.core.*@payload.*").expect("test");
+        assert!(payload.matches_with_path(&id, Some(r#"["payload"]["x.y"]"#)));
+
+        assert!(GtsWildcard::new(r#"gts.x.core.*@meta["unterminated]"#).is_err());
+    }
+
+    #[test]
+    fn test_gts_wildcard_rejects_invalid_attr_path() {
+        for pattern in [
+            "gts.x.core.events.event.v1~@",
+            "gts.x.core.events.event.v1~@payload..body",
+            "gts.x.core.events.event.v1~@payload.*.body",
+            "gts.x.core.events.event.v1~@items[x]",
+            "gts.x.core.events.event.v1~@items[1",
+            "gts.x.core.events.event.v1~@items[1]]",
+            "gts.x.core.events.event.v1~@pay load",
+        ] {
+            assert!(GtsWildcard::new(pattern).is_err(), "{pattern}");
+        }
+
+        let pattern = GtsWildcard::new("gts.x.core.events.event.v1~@payload.*").expect("test");
+        let id = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert!(!pattern.matches_with_path(&id, Some("payload.*")));
+        assert!(!pattern.matches_with_path(&id, Some("payload..body")));
+    }
+
+    #[test]
+    fn test_gts_error_serde_shape() {
+        let err = GtsID::new("gts.x.core.events.event.v1.0.extra~").unwrap_err();
+        let display = err.to_string();
+        let value = serde_json::to_value(&err).expect("test");
+        assert_eq!(value["kind"], "invalid_segment");
+        assert_eq!(value["num"], 1);
+        assert_eq!(value["offset"], 4);
+        assert_eq!(value["segment"], "x.core.events.event.v1.0.extra~");
+        assert_eq!(value["cause"], "Too many tokens");
+
+        let back: GtsError = serde_json::from_value(value).expect("test");
+        assert_eq!(back.to_string(), display);
+
+        let err = GtsError::InvalidWildcard {
+            pattern: "gts.x.*.y".to_owned(),
+            cause: "bad".to_owned(),
+        };
+        assert_eq!(
+            serde_json::to_value(&err).expect("test"),
+            serde_json::json!({"kind": "invalid_wildcard", "pattern": "gts.x.*.y", "cause": "bad"})
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid GTS wildcard pattern: gts.x.*.y: bad"
+        );
+
+        let err = GtsError::InvalidId {
+            id: "x".to_owned(),
+            cause: "bad".to_owned(),
+        };
+        let value = serde_json::to_value(&err).expect("test");
+        assert_eq!(
+            value,
+            serde_json::json!({"kind": "invalid_id", "id": "x", "cause": "bad"})
+        );
+        let back: GtsError = serde_json::from_value(value).expect("test");
+        assert_eq!(back.to_string(), err.to_string());
+    }
+
+    #[test]
+    fn test_gts_id_tilde_splitting() {
+        let single = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert_eq!(single.gts_id_segments.len(), 1);
+        assert!(single.gts_id_segments[0].is_type);
+
+        let none = GtsID::new("gts.x.core.events.event.v1.0").expect("test");
+        assert_eq!(none.gts_id_segments.len(), 1);
+        assert!(!none.gts_id_segments[0].is_type);
+
+        let chained =
+            GtsID::new("gts.x.core.events.event.v1~x.app.orders.created.v1.0").expect("test");
+        let segments: Vec<&str> = chained
+            .segments_iter()
+            .map(|seg| seg.segment.as_str())
+            .collect();
+        assert_eq!(
+            segments,
+            vec!["x.core.events.event.v1~", "x.app.orders.created.v1.0"]
+        );
+        assert_eq!(chained.gts_id_segments[1].offset, 27);
+
+        let cause = |id: &str| match GtsID::new(id) {
+            Err(GtsError::InvalidId { cause, .. }) => cause,
+            other => panic!("expected InvalidId for {id}, got {other:?}"),
+        };
+        assert_eq!(
+            cause("gts.x.core.events.event.v1~~"),
+            "GTS segment #2 @ offset 27 is empty: doubled '~'"
+        );
+        assert_eq!(
+            cause("gts.x.core.events.event.v1~~x.app.orders.created.v1.0"),
+            "GTS segment #2 @ offset 27 is empty: doubled '~'"
+        );
+        assert_eq!(
+            cause("gts.~x.core.events.event.v1~"),
+            "GTS segment #1 @ offset 4 is empty: stray '~'"
+        );
+        assert_eq!(cause("gts."), "GTS segment #1 @ offset 4 is empty");
+    }
+
+    #[test]
+    fn test_gts_id_version_compatible_with() {
+        let id = |s: &str| GtsID::new(s).expect("test");
+        let v1_0 = id("gts.x.core.events.event.v1~x.app.orders.created.v1.0");
+        let v1_2 = id("gts.x.core.events.event.v1~x.app.orders.created.v1.2");
+        let v1 = id("gts.x.core.events.event.v1~x.app.orders.created.v1");
+        let v2_0 = id("gts.x.core.events.event.v1~x.app.orders.created.v2.0");
+        let other = id("gts.x.core.events.event.v1~x.app.orders.updated.v1.0");
+        let parent_v2 = id("gts.x.core.events.event.v2~x.app.orders.created.v1.0");
+
+        let cases = [
+            (&v1_0, &v1_0, [true, true, true]),
+            (&v1_0, &v1_2, [false, true, true]),
+            (&v1_2, &v1_0, [false, false, true]),
+            (&v1, &v1_0, [true, true, true]),
+            (&v1_2, &v1, [false, false, true]),
+            (&v1_0, &v2_0, [false, false, false]),
+            (&v1_0, &other, [false, false, false]),
+            (&v1_0, &parent_v2, [false, false, false]),
+        ];
+        let policies = [
+            VersionPolicy::ExactMinor,
+            VersionPolicy::AtMostMinor,
+            VersionPolicy::SameMajor,
+        ];
+        for (a, b, expected) in cases {
+            for (policy, expected) in policies.into_iter().zip(expected) {
+                assert_eq!(
+                    a.version_compatible_with(b, policy),
+                    expected,
+                    "{} -> {}",
+                    a.id,
+                    b.id
+                );
+            }
+        }
+
+        let ty = id("gts.x.core.events.event.v1~");
+        assert!(ty.version_compatible_with(&ty, VersionPolicy::ExactMinor));
+        assert!(!ty.version_compatible_with(
+            &id("gts.x.core.events.event.v1.0"),
+            VersionPolicy::SameMajor
+        ));
+    }
+
+    #[test]
+    fn test_gts_error_offset_context() {
+        let input = "gts.x.core.events.event.v1~x.app.orders.9created.v1";
+        let err = GtsID::new(input).unwrap_err();
+        let context = err.offset_context(input).expect("test");
+        let lines: Vec<&str> = context.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], err.to_string());
+        assert_eq!(lines[1], format!("  {input}"));
+        let caret = lines[2].find('^').expect("test") - 2;
+        assert_eq!(caret, 27);
+        assert_eq!(&input[caret..], "x.app.orders.9created.v1");
+        assert_eq!(
+            lines[2].trim_start().len(),
+            "x.app.orders.9created.v1".len()
+        );
+
+        // Leading whitespace is trimmed before parsing but still shifts the caret
+        let padded = format!("  {input}");
+        let context = GtsID::new(&padded)
+            .unwrap_err()
+            .offset_context(&padded)
+            .expect("test");
+        let caret_line = context.lines().nth(2).expect("test");
+        assert_eq!(caret_line.find('^'), Some(2 + 2 + 27));
+
+        let err = GtsID::new("gts.X.core.events.event.v1~").unwrap_err();
+        assert!(err.offset_context("gts.X.core.events.event.v1~").is_none());
+    }
+
+    #[test]
+    fn test_gts_id_type_marker_round_trip() {
+        let instance = GtsID::new("gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0")
+            .expect("test");
+        let as_type = instance.with_type_marker().expect("test");
+        assert_eq!(
+            as_type.id,
+            "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0~"
+        );
+        assert!(as_type.is_type());
+        assert!(as_type.gts_id_segments.iter().all(|seg| seg.is_type));
+        assert_eq!(as_type, GtsID::new(&as_type.id).expect("test"));
+        assert_eq!(as_type.without_type_marker().expect("test"), instance);
+        assert!(!instance.last_segment().expect("test").is_type);
+        assert!(instance.gts_id_segments[0].is_type);
+
+        let single = GtsID::new("gts.x.core.events.event.v1.2~").expect("test");
+        let stripped = single.without_type_marker().expect("test");
+        assert_eq!(stripped.id, "gts.x.core.events.event.v1.2");
+        assert!(!stripped.is_type());
+        assert!(!stripped.last_segment().expect("test").is_type);
+        assert_eq!(stripped.with_type_marker().expect("test"), single);
+
+        // Already in the requested form
+        assert_eq!(single.with_type_marker().expect("test"), single);
+        assert_eq!(instance.without_type_marker().expect("test"), instance);
+
+        // A versionless type reference has no instance form
+        let versionless = GtsID::new("gts.x.core.events.event~").expect("test");
+        assert!(versionless.without_type_marker().is_err());
+    }
+
+    #[test]
+    fn test_parse_diagnostics_reports_every_error() {
+        assert!(
+            GtsID::parse_diagnostics("gts.x.core.events.event.v1~x.app.items.item.v1.0").is_empty()
+        );
+
+        // Both the second and the fourth segment are invalid
+        let input = "gts.x.core.events.event.v1~x.app.items~~x.app.orders.order.v1.0.0.0";
+        let errors = GtsID::parse_diagnostics(input);
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            &errors[0],
+            GtsError::InvalidSegment {
+                num: 2,
+                offset: 27,
+                ..
+            }
+        ));
+        assert!(errors[1]
+            .to_string()
+            .contains("GTS segment #3 @ offset 39 is empty: doubled '~'"));
+        assert!(matches!(
+            &errors[2],
+            GtsError::InvalidSegment { num: 4, .. }
+        ));
+        // `new` still reports only the first of them
+        assert_eq!(
+            GtsID::new(input).unwrap_err().to_string(),
+            errors[0].to_string()
+        );
+
+        // Id-level problems are collected too
+        let errors = GtsID::parse_diagnostics("gts.X.core-events.event.v1~");
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[..2]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                GtsError::InvalidId {
+                    id: "gts.X.core-events.event.v1~".to_owned(),
+                    cause: "Must be lower case".to_owned()
+                }
+                .to_string(),
+                GtsError::InvalidId {
+                    id: "gts.X.core-events.event.v1~".to_owned(),
+                    cause: "Must not contain '-'".to_owned()
+                }
+                .to_string(),
+            ]
+        );
+        assert_eq!(GtsID::parse_diagnostics("x.core.events.event.v1~").len(), 1);
+    }
+
+    #[test]
+    fn test_gts_id_builder() {
+        let type_id = GtsIdBuilder::new("x", "core", "events", "event", 1)
+            .is_type(true)
+            .build()
+            .expect("test");
+        assert_eq!(type_id.id, "gts.x.core.events.event.v1~");
+        assert!(type_id.is_type());
+
+        let instance_id = GtsIdBuilder::new("x", "core", "events", "event", 1)
+            .chain("x", "app", "orders", "created", 1)
+            .minor(0)
+            .build()
+            .expect("test");
+        assert_eq!(
+            instance_id.id,
+            "gts.x.core.events.event.v1~x.app.orders.created.v1.0"
+        );
+        assert_eq!(instance_id.gts_id_segments.len(), 2);
+        assert!(!instance_id.is_type());
+
+        let err = GtsIdBuilder::new("X", "core", "events", "event", 1)
+            .is_type(true)
+            .build();
+        assert!(matches!(err, Err(GtsError::InvalidId { .. })));
+    }
+
+    #[test]
+    fn test_gts_parser_reuses_repeated_segments() {
+        let ids: Vec<String> = (0..10_000)
+            .map(|i| {
+                format!(
+                    "gts.x.core.events.type.v1~x.app{}.orders.order_{}.v1.{}",
+                    i % 5,
+                    i % 20,
+                    i % 3
+                )
+            })
+            .collect();
+
+        let mut parser = GtsParser::new();
+        for id in &ids {
+            let parsed = GtsID::new_with_parser(id, &mut parser).expect("test");
+            assert_eq!(parsed, GtsID::new(id).expect("test"));
+        }
+        // 20_000 segments, but only 1 shared type segment plus 60 distinct instance
+        // segments (lcm of 5, 20 and 3) are parsed
+        assert_eq!(parser.segments_parsed(), 61);
+        assert_eq!(parser.cached_segments(), 61);
+
+        // Offsets and numbers come from the ID, not from the cached copy
+        let chained = parser
+            .parse("gts.x.app0.orders.order_0.v1.0~x.core.events.type.v1~")
+            .expect("test");
+        assert_eq!(chained.gts_id_segments[1].num, 2);
+        assert_eq!(chained.gts_id_segments[1].offset, 31);
+        assert!(chained.gts_id_segments[1].is_type);
+
+        // Invalid segments are reported as usual and not cached
+        let before = parser.cached_segments();
+        assert!(parser.parse("gts.x.core.events.type.v1~x.Bad").is_err());
+        assert!(parser.parse("gts.x.core.events.type.v1~x.bad").is_err());
+        assert_eq!(parser.cached_segments(), before);
+
+        let limited = GtsParseOptions {
+            max_segments: Some(1),
+            ..GtsParseOptions::default()
+        };
+        let mut limited = GtsParser::with_options(limited);
+        assert!(limited.parse(&ids[0]).is_err());
+
+        parser.clear();
+        assert_eq!(parser.cached_segments(), 0);
+        assert_eq!(parser.segments_parsed(), 0);
+    }
+
+    #[test]
+    fn test_gts_id_new_with_options() {
+        let id = "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0~";
+        assert!(GtsID::new(id).is_ok());
+
+        let short = GtsParseOptions {
+            max_length: 32,
+            ..GtsParseOptions::default()
+        };
+        let err = GtsID::new_with_options(id, &short).unwrap_err();
+        assert!(err.to_string().contains("Too long"));
+        assert!(GtsID::new_with_options("gts.x.core.events.type.v1~", &short).is_ok());
+
+        let single = GtsParseOptions {
+            max_segments: Some(1),
+            ..GtsParseOptions::default()
+        };
+        let err = GtsID::new_with_options(id, &single).unwrap_err();
+        assert!(err.to_string().contains("Too many segments"));
+
+        let no_marker = GtsParseOptions {
+            allow_type_marker: false,
+            ..GtsParseOptions::default()
+        };
+        assert!(GtsID::new_with_options(id, &no_marker).is_err());
+        assert!(GtsID::new_with_options(
+            "gts.x.core.events.type.v1~x.commerce.orders.order_placed.v1.0",
+            &no_marker
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_gts_wildcard_match_many_ids() {
+        let ids: Vec<GtsID> = (0..10_000)
+            .map(|i| {
+                let namespace = if i % 2 == 0 { "events" } else { "audit" };
+                GtsID::new(&format!(
+                    "gts.x.core.{namespace}.type{}.v1.{}~",
+                    i % 50,
+                    i % 7
+                ))
+                .expect("test")
+            })
+            .collect();
+
+        let mut pattern = GtsWildcard::new("gts.x.core.events.*").expect("test");
+        let exact = GtsWildcard::new("gts.x.core.events.type4.v1~").expect("test");
+        // Matching relies on the precomputed wildcard position, not the pattern string
+        pattern.id = String::new();
+
+        assert_eq!(
+            ids.iter().filter(|id| id.wildcard_match(&pattern)).count(),
+            5_000
+        );
+        assert_eq!(
+            ids.iter().filter(|id| id.wildcard_match(&exact)).count(),
+            200
+        );
+    }
+
+    #[test]
+    fn test_gts_id_is_ancestor_of() {
+        let base = GtsID::new("gts.x.core.events.type.v1~").expect("test");
+        let child =
+            GtsID::new("gts.x.core.events.type.v1~x.commerce.orders.placed.v1.0~").expect("test");
+        let grandchild = GtsID::new(
+            "gts.x.core.events.type.v1~x.commerce.orders.placed.v1.0~x.shop.web.checkout.v1.2",
+        )
+        .expect("test");
+        let sibling = GtsID::new("gts.x.core.events.type.v1~x.commerce.orders.cancelled.v1.0~")
+            .expect("test");
+        let unrelated = GtsID::new("gts.y.other.pkg.thing.v1~").expect("test");
+
+        // Direct parent and grandparent
+        assert!(base.is_ancestor_of(&child));
+        assert!(child.is_ancestor_of(&grandchild));
+        assert!(base.is_ancestor_of(&grandchild));
+
+        // Minor versions are ignored when the ancestor omits them
+        let base_minor = GtsID::new("gts.x.core.events.type.v1.3~").expect("test");
+        assert!(!base_minor.is_ancestor_of(&child));
+        let child_any_minor =
+            GtsID::new("gts.x.core.events.type.v1~x.commerce.orders.placed.v1~").expect("test");
+        assert!(child_any_minor.is_ancestor_of(&grandchild));
+
+        // Siblings, unrelated IDs, itself and descendants are not ancestors
+        assert!(!sibling.is_ancestor_of(&grandchild));
+        assert!(!unrelated.is_ancestor_of(&child));
+        assert!(!child.is_ancestor_of(&child));
+        assert!(!grandchild.is_ancestor_of(&base));
+
+        // An instance ID has no type marker on its boundary segment
+        let instance = GtsID::new("gts.x.core.events.type.v1.0").expect("test");
+        assert!(!instance.is_ancestor_of(&child));
+    }
+
+    #[test]
+    fn test_gts_id_same_type() {
+        let v1_0 = GtsID::new("gts.x.core.events.event.v1.0").expect("test");
+        let v1_3 = GtsID::new("gts.x.core.events.event.v1.3").expect("test");
+        let v1 = GtsID::new("gts.x.core.events.event.v1").expect("test");
+        let v2_0 = GtsID::new("gts.x.core.events.event.v2.0").expect("test");
+        let schema = GtsID::new("gts.x.core.events.event.v1.0~").expect("test");
+
+        assert!(v1_0.same_type(&v1_3));
+        assert!(v1_0.same_type(&v1));
+        assert_ne!(v1_0, v1_3);
+        assert!(!v1_0.same_type(&v2_0));
+        assert!(!v1_0.same_type(&schema));
+
+        let chained_a =
+            GtsID::new("gts.x.core.events.type.v1.0~x.commerce.orders.placed.v1.0").expect("test");
+        let chained_b =
+            GtsID::new("gts.x.core.events.type.v1.1~x.commerce.orders.placed.v1.4").expect("test");
+        assert!(chained_a.same_type(&chained_b));
+        assert!(!chained_a.same_type(&v1_0));
+    }
+
+    #[test]
+    fn test_gts_id_bump_versions() {
+        let id = GtsID::new("gts.x.core.events.event.v1.2").expect("test");
+        assert_eq!(
+            id.bump_minor().expect("test").id,
+            "gts.x.core.events.event.v1.3"
+        );
+        assert_eq!(
+            id.bump_major().expect("test").id,
+            "gts.x.core.events.event.v2.0"
+        );
+
+        let no_minor = GtsID::new("gts.x.core.events.event.v1~").expect("test");
+        assert_eq!(
+            no_minor.bump_minor().expect("test").id,
+            "gts.x.core.events.event.v1.1~"
+        );
+        assert_eq!(
+            no_minor.bump_major().expect("test").id,
+            "gts.x.core.events.event.v2~"
+        );
+
+        // Only the last segment changes and type markers are preserved
+        let chained =
+            GtsID::new("gts.x.core.events.type.v1~x.commerce.orders.placed.v1.0~").expect("test");
+        let bumped = chained.bump_minor().expect("test");
+        assert_eq!(
+            bumped.id,
+            "gts.x.core.events.type.v1~x.commerce.orders.placed.v1.1~"
+        );
+        assert!(bumped.is_type());
+        assert_eq!(bumped.gts_id_segments.len(), 2);
+
+        let versionless = GtsID::new("gts.x.core.events.event~").expect("test");
+        assert!(versionless.bump_minor().is_err());
+        assert!(versionless.bump_major().is_err());
+
+        let max_minor =
+            GtsID::new(&format!("gts.x.core.events.event.v1.{}", u32::MAX)).expect("test");
+        assert!(max_minor.bump_minor().is_err());
+    }
 }
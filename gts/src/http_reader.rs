@@ -0,0 +1,248 @@
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::entities::{GtsConfig, GtsEntity, GtsFile};
+use crate::store::GtsReader;
+
+/// A `GtsReader` over JSON documents served over HTTP, such as a schema registry.
+///
+/// Each body is parsed like a file's content: an object is one entity and an array
+/// holds several. Bodies are fetched once and cached for the lifetime of the reader;
+/// `reset` keeps the cache. Both `http` and `https` URLs can be fetched, the latter
+/// with rustls and the Mozilla root certificates.
+///
+/// # Panics
+/// The client is blocking: creating, using or dropping the reader from within an
+/// async runtime such as tokio panics. Call it from `spawn_blocking` or a plain
+/// thread there.
+pub struct GtsHttpReader {
+    urls: Vec<String>,
+    // `read_by_id` fetches `{base_url}/{id}` when set
+    base_url: Option<String>,
+    cfg: GtsConfig,
+    basic_auth: Option<(String, Option<String>)>,
+    client: reqwest::blocking::Client,
+    // URL -> parsed body, successful fetches only
+    bodies: RefCell<HashMap<String, Value>>,
+}
+
+impl GtsHttpReader {
+    /// Reads the documents at `urls`; `read_by_id` looks the ID up in them in order.
+    #[must_use]
+    pub fn new(urls: &[String], cfg: Option<GtsConfig>) -> Self {
+        GtsHttpReader {
+            urls: urls.to_vec(),
+            base_url: None,
+            cfg: cfg.unwrap_or_default(),
+            basic_auth: None,
+            client: reqwest::blocking::Client::new(),
+            bodies: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Reads `{base_url}/{id}` for each of `ids`, and lets `read_by_id` fetch any
+    /// other ID the same way.
+    #[must_use]
+    pub fn from_base_url(base_url: &str, ids: &[String], cfg: Option<GtsConfig>) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_owned();
+        let urls: Vec<String> = ids.iter().map(|id| format!("{base_url}/{id}")).collect();
+        GtsHttpReader {
+            base_url: Some(base_url),
+            ..Self::new(&urls, cfg)
+        }
+    }
+
+    /// Sends an HTTP basic `Authorization` header with every request.
+    #[must_use]
+    pub fn with_basic_auth(mut self, username: &str, password: Option<&str>) -> Self {
+        self.basic_auth = Some((username.to_owned(), password.map(str::to_owned)));
+        self
+    }
+
+    /// The parsed body at `url`, fetching it on first use. Failures are logged and
+    /// not cached, so a later call retries.
+    fn body(&self, url: &str) -> Option<Value> {
+        if let Some(body) = self.bodies.borrow().get(url) {
+            return Some(body.clone());
+        }
+
+        let mut request = self.client.get(url);
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, password.as_ref());
+        }
+        let body = match request
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+        {
+            Ok(response) => match response.text() {
+                Ok(text) => serde_json::from_str::<Value>(&text).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+        match body {
+            Ok(body) => {
+                self.bodies
+                    .borrow_mut()
+                    .insert(url.to_owned(), body.clone());
+                Some(body)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    fn entities_at(&self, url: &str) -> Vec<GtsEntity> {
+        let Some(content) = self.body(url) else {
+            return Vec::new();
+        };
+        let name = url.rsplit('/').next().unwrap_or(url).to_owned();
+        let file = GtsFile::new(url.to_owned(), name, content.clone());
+        let make_entity = |list_sequence: Option<usize>, item: &Value| {
+            GtsEntity::new(
+                Some(file.clone()),
+                list_sequence,
+                item,
+                Some(&self.cfg),
+                None,
+                false,
+                String::new(),
+                None,
+                None,
+            )
+        };
+        let entities: Vec<GtsEntity> = match content.as_array() {
+            Some(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| make_entity(Some(idx), item))
+                .collect(),
+            None => vec![make_entity(None, &content)],
+        };
+        entities
+            .into_iter()
+            .filter(|entity| entity.gts_id.is_some())
+            .collect()
+    }
+}
+
+impl GtsReader for GtsHttpReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        let this = &*self;
+        Box::new(this.urls.iter().flat_map(move |url| this.entities_at(url)))
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        let by_id = self
+            .base_url
+            .as_ref()
+            .map(|base_url| format!("{base_url}/{entity_id}"));
+        by_id
+            .iter()
+            .chain(self.urls.iter().filter(|url| Some(*url) != by_id.as_ref()))
+            .find_map(|url| {
+                self.entities_at(url).into_iter().find(|entity| {
+                    entity
+                        .gts_id
+                        .as_ref()
+                        .is_some_and(|gts_id| gts_id.id == entity_id)
+                })
+            })
+    }
+
+    fn reset(&mut self) {}
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_gts_http_reader_fetches_and_caches_entities() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+        use std::sync::{Arc, Mutex};
+
+        const SCHEMA_ID: &str = "gts.x.core.events.event.v1~";
+
+        // Serves SCHEMA_ID at /SCHEMA_ID and 404 elsewhere, recording each request
+        // line and its Authorization header
+        let listener = TcpListener::bind("127.0.0.1:0").expect("test");
+        let base_url = format!("http://{}/schemas", listener.local_addr().expect("test"));
+        let requests: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let seen = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.expect("test");
+                let mut reader = BufReader::new(stream.try_clone().expect("test"));
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).expect("test");
+                let mut auth = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("test");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("authorization") {
+                            auth = value.trim().to_owned();
+                        }
+                    }
+                }
+                let found = request_line.contains(&format!("/schemas/{SCHEMA_ID} "));
+                let (status, body) = if found {
+                    (
+                        "200 OK",
+                        json!({"$id": SCHEMA_ID, "type": "object"}).to_string(),
+                    )
+                } else {
+                    ("404 Not Found", String::new())
+                };
+                seen.lock()
+                    .expect("test")
+                    .push((request_line.trim().to_owned(), auth));
+                let headers = format!("Content-Length: {}\r\nConnection: close", body.len());
+                write!(stream, "HTTP/1.1 {status}\r\n{headers}\r\n\r\n{body}").expect("test");
+            }
+        });
+
+        let mut reader = GtsHttpReader::from_base_url(&base_url, &[SCHEMA_ID.to_owned()], None)
+            .with_basic_auth("ci", Some("secret"));
+
+        let ids: Vec<String> = reader
+            .iter()
+            .filter_map(|e| e.gts_id.map(|id| id.id))
+            .collect();
+        assert_eq!(ids, vec![SCHEMA_ID]);
+
+        // Served from the cache
+        let entity = reader.read_by_id(SCHEMA_ID).expect("test");
+        assert!(entity.is_schema);
+        assert_eq!(
+            entity.file.expect("test").path,
+            format!("{base_url}/{SCHEMA_ID}")
+        );
+        assert_eq!(reader.count(), 1);
+        assert_eq!(requests.lock().expect("test").len(), 1);
+
+        assert!(reader.read_by_id("gts.x.core.events.missing.v1~").is_none());
+
+        let requests = requests.lock().expect("test");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, format!("GET /schemas/{SCHEMA_ID} HTTP/1.1"));
+        assert_eq!(
+            requests[1].0,
+            "GET /schemas/gts.x.core.events.missing.v1~ HTTP/1.1"
+        );
+        // base64 of "ci:secret"
+        assert!(requests
+            .iter()
+            .all(|(_, auth)| auth == "Basic Y2k6c2VjcmV0"));
+    }
+}
@@ -1,18 +1,44 @@
+#[cfg(feature = "async")]
+pub mod async_reader;
 pub mod entities;
 pub mod files_reader;
 pub mod gts;
+#[cfg(feature = "http")]
+pub mod http_reader;
 pub mod ops;
 pub mod path_resolver;
 pub mod schema_cast;
 pub mod store;
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod test_utils;
 pub mod x_gts_ref;
 
 // Re-export commonly used types
-pub use entities::{GtsConfig, GtsEntity, GtsFile, ValidationError, ValidationResult};
-pub use files_reader::GtsFileReader;
-pub use gts::{GtsError, GtsID, GtsIdSegment, GtsWildcard};
+#[cfg(feature = "async")]
+pub use async_reader::AsyncGtsFileReader;
+pub use entities::{
+    diff_entities, EntityDiff, GtsConfig, GtsConfigBuilder, GtsConfigError, GtsEntity, GtsFile,
+    ValidationError, ValidationResult,
+};
+pub use files_reader::{GtsFileReader, GtsInvalidId, GtsReadStats};
+pub use gts::{
+    Effect, GtsError, GtsID, GtsIdBuilder, GtsIdSegment, GtsParseOptions, GtsParser, GtsWildcard,
+    GtsWildcardExpr, GtsWildcardOptions, VersionPolicy, WildcardRuleSet, GTS_UUID_NAMESPACE,
+};
+#[cfg(feature = "http")]
+pub use http_reader::GtsHttpReader;
 pub use ops::GtsOps;
-pub use path_resolver::JsonPathResolver;
-pub use schema_cast::{GtsEntityCastResult, SchemaCastError};
-pub use store::{GtsReader, GtsStore, GtsStoreQueryResult, StoreError};
+pub use path_resolver::{
+    parse_attribute_path, resolve_attribute_path, AttrPathSegment, JsonPathResolver,
+};
+pub use schema_cast::{
+    CastDirection, CastOptions, CastTimings, CompatSummary, CompatibilityCache, DefaultPolicy,
+    FlattenConflict, FlattenedSchema, GtsEntityCastResult, SchemaCastError, SchemaDialect,
+    SchemaFlattener, SchemaResolver,
+};
+pub use store::{
+    find_duplicate_ids, GtsChainedReader, GtsMemoryReader, GtsReader, GtsSourceLocation, GtsStore,
+    GtsStoreQueryResult, JsonArrayStreamReader, StoreError,
+};
 pub use x_gts_ref::{XGtsRefValidationError, XGtsRefValidator};
@@ -35,7 +35,7 @@ impl JsonPathResolver {
     fn split_raw_parts(norm: &str) -> Vec<String> {
         norm.split('.')
             .filter(|s| !s.is_empty())
-            .map(ToString::to_string)
+            .map(str::to_owned)
             .collect()
     }
 
@@ -73,16 +73,40 @@ impl JsonPathResolver {
         out
     }
 
-    fn parts(path: &str) -> Vec<String> {
+    /// Splits `path` into keys and indices, or `None` if a path with quoted keys is
+    /// malformed.
+    fn parts(path: &str) -> Option<Vec<AttrPathSegment>> {
+        // Quoted keys may hold the separators the lenient split below relies on
+        if path.contains("[\"") {
+            return parse_attribute_path(path);
+        }
+
         let norm = Self::normalize(path);
         let raw = Self::split_raw_parts(&norm);
         let mut parts = Vec::new();
 
         for seg in raw {
-            parts.extend(Self::parse_part(&seg));
+            parts.extend(Self::parse_part(&seg).into_iter().map(|p| {
+                match p
+                    .strip_prefix('[')
+                    .and_then(|p| p.strip_suffix(']'))
+                    .and_then(|idx| idx.parse::<usize>().ok())
+                {
+                    Some(idx) => AttrPathSegment::Index(idx),
+                    None => AttrPathSegment::Key(p),
+                }
+            }));
         }
 
-        parts
+        Some(parts)
+    }
+
+    /// How `segment` is named in error messages.
+    fn segment_label(segment: &AttrPathSegment) -> String {
+        match segment {
+            AttrPathSegment::Key(key) => key.clone(),
+            AttrPathSegment::Index(idx) => format!("[{idx}]"),
+        }
     }
 
     fn list_available(node: &Value, prefix: &str, out: &mut Vec<String>) {
@@ -131,27 +155,26 @@ impl JsonPathResolver {
         self.error = None;
         self.available_fields = None;
 
-        let parts = Self::parts(path);
+        let Some(parts) = Self::parts(path) else {
+            return self.failure(path, &format!("Invalid attribute path '{path}'"));
+        };
         let mut cur = self.content.clone();
 
-        for p in parts {
+        for segment in parts {
+            let p = Self::segment_label(&segment);
             match &cur {
                 Value::Array(arr) => {
-                    let idx = if p.starts_with('[') && p.ends_with(']') {
-                        let idx_str = &p[1..p.len() - 1];
-                        if let Ok(i) = idx_str.parse::<usize>() {
-                            i
-                        } else {
-                            self.error = Some(format!("Expected list index at segment '{p}'"));
-                            self.available_fields = Some(Self::collect_from(&cur));
-                            return self;
+                    let idx = match &segment {
+                        AttrPathSegment::Index(i) => *i,
+                        AttrPathSegment::Key(key) => {
+                            if let Ok(i) = key.parse::<usize>() {
+                                i
+                            } else {
+                                self.error = Some(format!("Expected list index at segment '{p}'"));
+                                self.available_fields = Some(Self::collect_from(&cur));
+                                return self;
+                            }
                         }
-                    } else if let Ok(i) = p.parse::<usize>() {
-                        i
-                    } else {
-                        self.error = Some(format!("Expected list index at segment '{p}'"));
-                        self.available_fields = Some(Self::collect_from(&cur));
-                        return self;
                     };
 
                     if idx >= arr.len() {
@@ -163,15 +186,15 @@ impl JsonPathResolver {
                     cur = arr[idx].clone();
                 }
                 Value::Object(map) => {
-                    if p.starts_with('[') && p.ends_with(']') {
+                    let AttrPathSegment::Key(key) = &segment else {
                         self.error = Some(format!(
                             "Path not found at segment '{p}' in '{path}', see available fields"
                         ));
                         self.available_fields = Some(Self::collect_from(&cur));
                         return self;
-                    }
+                    };
 
-                    if let Some(v) = map.get(&p) {
+                    if let Some(v) = map.get(key) {
                         cur = v.clone();
                     } else {
                         self.error = Some(format!(
@@ -209,6 +232,108 @@ impl JsonPathResolver {
     }
 }
 
+/// One step of an attribute path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrPathSegment {
+    /// An object property.
+    Key(String),
+    /// An array element.
+    Index(usize),
+}
+
+/// Parses an attribute path such as the one after `@` in a GTS reference.
+///
+/// A path is a sequence of segments:
+/// - a bare property name, separated from the previous segment by `.`; it must not
+///   be empty or contain `.`, `[`, `]`, `@`, `*`, `"` or whitespace,
+/// - an array index in brackets, e.g. `items[2]`,
+/// - a quoted property name in brackets, e.g. `["weird.key"]`, which may contain
+///   any character; `"` and `\` are escaped with a backslash.
+///
+/// Bracketed segments follow the previous segment directly and may start the path,
+/// so `meta["a@b"].items[0]` and `["x.y"]` are both valid. Returns `None` for an
+/// invalid or empty path.
+#[must_use]
+pub fn parse_attribute_path(path: &str) -> Option<Vec<AttrPathSegment>> {
+    fn parse_quoted(quoted: &str) -> Option<(String, usize)> {
+        let mut key = String::new();
+        let mut escaped = false;
+        for (i, ch) in quoted.char_indices() {
+            match (escaped, ch) {
+                (true, '"' | '\\') => {
+                    key.push(ch);
+                    escaped = false;
+                }
+                (true, _) => return None,
+                (false, '\\') => escaped = true,
+                (false, '"') => return Some((key, i + 1)),
+                (false, _) => key.push(ch),
+            }
+        }
+        None
+    }
+
+    let mut segments = Vec::new();
+    let mut rest = path;
+    let mut expect_name = !rest.starts_with('[');
+    while expect_name || !rest.is_empty() {
+        if expect_name {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            if name.is_empty()
+                || name.contains([']', '@', '*', '"'])
+                || name.contains(char::is_whitespace)
+            {
+                return None;
+            }
+            segments.push(AttrPathSegment::Key(name.to_owned()));
+            rest = &rest[end..];
+            expect_name = false;
+        } else if let Some(after_dot) = rest.strip_prefix('.') {
+            rest = after_dot;
+            expect_name = true;
+        } else {
+            let inner = rest.strip_prefix('[')?;
+            if let Some(quoted) = inner.strip_prefix('"') {
+                let (key, len) = parse_quoted(quoted)?;
+                rest = quoted[len..].strip_prefix(']')?;
+                segments.push(AttrPathSegment::Key(key));
+            } else {
+                let close = inner.find(']')?;
+                let digits = &inner[..close];
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                segments.push(AttrPathSegment::Index(digits.parse().ok()?));
+                rest = &inner[close + 1..];
+            }
+        }
+    }
+    Some(segments)
+}
+
+/// Resolves an attribute path such as the one after `@` in a GTS reference against
+/// `instance`, returning the referenced sub-value.
+///
+/// Paths use the dotted notation of the cast module, e.g. `order.items[2].sku`, with
+/// the quoting of [`parse_attribute_path`] for keys such as `["weird.key"]`. An
+/// empty path refers to `instance` itself. Returns `None` when the path is invalid,
+/// a property or index does not exist, or a segment descends into a scalar.
+#[must_use]
+pub fn resolve_attribute_path<'a>(instance: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(instance);
+    }
+    parse_attribute_path(path)?
+        .iter()
+        .try_fold(instance, |cur, segment| match (cur, segment) {
+            (Value::Array(arr), AttrPathSegment::Index(index)) => arr.get(*index),
+            (Value::Array(arr), AttrPathSegment::Key(key)) => arr.get(key.parse::<usize>().ok()?),
+            (Value::Object(map), AttrPathSegment::Key(key)) => map.get(key),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -218,34 +343,170 @@ mod tests {
     #[test]
     fn test_resolve_simple_path() {
         let content = json!({"field": "value"});
-        let resolver = JsonPathResolver::new("gts.test.v1~".to_string(), content);
+        let resolver = JsonPathResolver::new("gts.test.v1~".to_owned(), content);
         let result = resolver.resolve("field");
         assert!(result.resolved);
-        assert_eq!(result.value, Some(Value::String("value".to_string())));
+        assert_eq!(result.value, Some(Value::String("value".to_owned())));
     }
 
     #[test]
     fn test_resolve_nested_path() {
         let content = json!({"outer": {"inner": "value"}});
-        let resolver = JsonPathResolver::new("gts.test.v1~".to_string(), content);
+        let resolver = JsonPathResolver::new("gts.test.v1~".to_owned(), content);
         let result = resolver.resolve("outer.inner");
         assert!(result.resolved);
-        assert_eq!(result.value, Some(Value::String("value".to_string())));
+        assert_eq!(result.value, Some(Value::String("value".to_owned())));
     }
 
     #[test]
     fn test_resolve_array_index() {
         let content = json!({"items": [1, 2, 3]});
-        let resolver = JsonPathResolver::new("gts.test.v1~".to_string(), content);
+        let resolver = JsonPathResolver::new("gts.test.v1~".to_owned(), content);
         let result = resolver.resolve("items[1]");
         assert!(result.resolved);
         assert_eq!(result.value, Some(Value::Number(2.into())));
     }
 
+    #[test]
+    fn test_resolve_attribute_path() {
+        let instance = json!({
+            "order": {
+                "id": "o-1",
+                "items": [
+                    {"sku": "a", "tags": ["new"]},
+                    {"sku": "b"},
+                    {"sku": "c", "tags": ["sale", "last"]}
+                ]
+            }
+        });
+
+        assert_eq!(
+            resolve_attribute_path(&instance, "order.id"),
+            Some(&json!("o-1"))
+        );
+        assert_eq!(
+            resolve_attribute_path(&instance, "order.items[2]"),
+            Some(&json!({"sku": "c", "tags": ["sale", "last"]}))
+        );
+        assert_eq!(
+            resolve_attribute_path(&instance, "order.items[2].tags[1]"),
+            Some(&json!("last"))
+        );
+        assert_eq!(resolve_attribute_path(&instance, ""), Some(&instance));
+
+        for missing in [
+            "order.total",
+            "order.items[3]",
+            "order.items[x]",
+            "order.items[1].tags",
+            "order.id.length",
+            "order[0]",
+        ] {
+            assert_eq!(
+                resolve_attribute_path(&instance, missing),
+                None,
+                "{missing}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_attribute_path_quoting() {
+        use AttrPathSegment::{Index, Key};
+
+        assert_eq!(
+            parse_attribute_path(r#"meta["weird.key"]["a@b"].items[0]"#),
+            Some(vec![
+                Key("meta".to_owned()),
+                Key("weird.key".to_owned()),
+                Key("a@b".to_owned()),
+                Key("items".to_owned()),
+                Index(0),
+            ])
+        );
+        assert_eq!(
+            parse_attribute_path(r#"["x.y"].z"#),
+            Some(vec![Key("x.y".to_owned()), Key("z".to_owned())])
+        );
+        assert_eq!(
+            parse_attribute_path(r#"["say \"hi\" \\ bye"]"#),
+            Some(vec![Key(r#"say "hi" \ bye"#.to_owned())])
+        );
+
+        for invalid in [
+            "",
+            "a..b",
+            "a.",
+            ".a",
+            "a.b@c",
+            r#"a["b"#,
+            r#"a["b"c]"#,
+            r#"a["\n"]"#,
+            r#"a."b""#,
+            "a[]",
+        ] {
+            assert_eq!(parse_attribute_path(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_quoted_keys() {
+        let instance = json!({
+            "labels": {"app.kubernetes.io/name": "gts", "owner@team": "core"},
+            "x.y": [{"z": 1}]
+        });
+
+        assert_eq!(
+            resolve_attribute_path(&instance, r#"labels["app.kubernetes.io/name"]"#),
+            Some(&json!("gts"))
+        );
+        assert_eq!(
+            resolve_attribute_path(&instance, r#"labels["owner@team"]"#),
+            Some(&json!("core"))
+        );
+        assert_eq!(
+            resolve_attribute_path(&instance, r#"["x.y"][0].z"#),
+            Some(&json!(1))
+        );
+        assert_eq!(
+            resolve_attribute_path(&instance, "labels.app.kubernetes.io/name"),
+            None
+        );
+
+        let result = JsonPathResolver::new("gts.test.v1~".to_owned(), instance.clone())
+            .resolve(r#"labels["owner@team"]"#);
+        assert!(result.resolved);
+        assert_eq!(result.value, Some(json!("core")));
+
+        // Malformed quoted keys fail instead of resolving to the whole document
+        for invalid in [r#"labels["a.b"#, r#"labels["a.b"]junk"#] {
+            assert_eq!(resolve_attribute_path(&instance, invalid), None);
+            let result =
+                JsonPathResolver::new("gts.test.v1~".to_owned(), instance.clone()).resolve(invalid);
+            assert!(!result.resolved, "{invalid}");
+            assert_eq!(result.value, None);
+            assert_eq!(
+                result.error,
+                Some(format!("Invalid attribute path '{invalid}'"))
+            );
+        }
+
+        // A quoted key stays a key even when it looks like an index
+        let indexed = json!({"[0]": {"a": 1}});
+        assert_eq!(
+            resolve_attribute_path(&indexed, r#"["[0]"].a"#),
+            Some(&json!(1))
+        );
+        let result =
+            JsonPathResolver::new("gts.test.v1~".to_owned(), indexed).resolve(r#"["[0]"].a"#);
+        assert!(result.resolved, "{:?}", result.error);
+        assert_eq!(result.value, Some(json!(1)));
+    }
+
     #[test]
     fn test_resolve_missing_path() {
         let content = json!({"field": "value"});
-        let resolver = JsonPathResolver::new("gts.test.v1~".to_string(), content);
+        let resolver = JsonPathResolver::new("gts.test.v1~".to_owned(), content);
         let result = resolver.resolve("missing");
         assert!(!result.resolved);
         assert!(result.error.is_some());
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
 use thiserror::Error;
 
-use crate::entities::GtsEntity;
+use crate::entities::{GtsConfig, GtsEntity};
+use crate::files_reader::GtsFileReader;
 use crate::gts::{GtsID, GtsWildcard};
-use crate::schema_cast::GtsEntityCastResult;
+use crate::schema_cast::{CastDirection, GtsEntityCastResult, SchemaResolver};
 
 #[derive(Debug, Error)]
 pub enum StoreError {
@@ -33,6 +37,237 @@ pub trait GtsReader: Send {
     fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_>;
     fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity>;
     fn reset(&mut self);
+
+    /// Number of entities `iter` would yield.
+    fn count(&mut self) -> usize {
+        self.iter().count()
+    }
+
+    /// Reads every entity into a map keyed by GTS ID. Entities without an ID are
+    /// skipped and, when an ID occurs more than once, the last entity wins.
+    fn read_into_map(&mut self) -> HashMap<String, GtsEntity> {
+        let mut map = HashMap::new();
+        for entity in self.iter() {
+            let Some(id) = entity.gts_id.as_ref().map(|gts_id| gts_id.id.clone()) else {
+                continue;
+            };
+            if map.contains_key(&id) {
+                tracing::debug!("Entity {} occurs more than once, keeping the last one", id);
+            }
+            map.insert(id, entity);
+        }
+        map
+    }
+}
+
+/// A `GtsReader` over entities held in memory, with random access by GTS ID.
+pub struct GtsMemoryReader {
+    entities: Vec<GtsEntity>,
+    by_id: HashMap<String, usize>,
+}
+
+impl GtsMemoryReader {
+    /// Wraps `entities`; entities without a GTS ID are dropped and, when an ID
+    /// occurs more than once, `read_by_id` returns the first one.
+    #[must_use]
+    pub fn new(entities: Vec<GtsEntity>) -> Self {
+        let entities: Vec<GtsEntity> = entities
+            .into_iter()
+            .filter(|entity| entity.gts_id.is_some())
+            .collect();
+
+        let mut by_id = HashMap::new();
+        for (idx, entity) in entities.iter().enumerate() {
+            if let Some(ref gts_id) = entity.gts_id {
+                by_id.entry(gts_id.id.clone()).or_insert(idx);
+            }
+        }
+
+        GtsMemoryReader { entities, by_id }
+    }
+
+    /// Builds entities from JSON documents the same way `GtsFileReader` does for
+    /// file contents, detecting IDs with `cfg`.
+    #[must_use]
+    pub fn from_values(values: &[Value], cfg: &GtsConfig) -> Self {
+        let entities = values
+            .iter()
+            .map(|content| {
+                GtsEntity::new(
+                    None,
+                    None,
+                    content,
+                    Some(cfg),
+                    None,
+                    false,
+                    String::new(),
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        Self::new(entities)
+    }
+}
+
+impl GtsReader for GtsMemoryReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        Box::new(self.entities.iter().cloned())
+    }
+
+    fn count(&mut self) -> usize {
+        self.entities.len()
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        self.by_id
+            .get(entity_id)
+            .map(|&idx| self.entities[idx].clone())
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// A `GtsReader` that iterates several readers in turn as one stream.
+pub struct GtsChainedReader {
+    readers: Vec<Box<dyn GtsReader>>,
+}
+
+impl GtsChainedReader {
+    /// Chains `readers`; earlier readers take precedence in `read_by_id` when an ID
+    /// occurs in more than one of them.
+    #[must_use]
+    pub fn new(readers: Vec<Box<dyn GtsReader>>) -> Self {
+        GtsChainedReader { readers }
+    }
+}
+
+impl GtsReader for GtsChainedReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        Box::new(self.readers.iter_mut().flat_map(|reader| reader.iter()))
+    }
+
+    fn count(&mut self) -> usize {
+        self.readers.iter_mut().map(|reader| reader.count()).sum()
+    }
+
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        self.readers
+            .iter()
+            .find_map(|reader| reader.read_by_id(entity_id))
+    }
+
+    fn reset(&mut self) {
+        for reader in &mut self.readers {
+            reader.reset();
+        }
+    }
+}
+
+/// Reads entities from a single file holding a JSON array, either as the whole
+/// document or as the `entities` member of a top-level object.
+///
+/// Elements are parsed one at a time, so the document is never held in memory as a
+/// whole; each entity's `file.content` holds only its own element. Reading stops at
+/// the first malformed element, which is logged as a warning.
+pub struct JsonArrayStreamReader {
+    path: PathBuf,
+    cfg: GtsConfig,
+}
+
+impl JsonArrayStreamReader {
+    #[must_use]
+    pub fn new(path: &str, cfg: Option<GtsConfig>) -> Self {
+        JsonArrayStreamReader {
+            path: PathBuf::from(shellexpand::tilde(path).to_string()),
+            cfg: cfg.unwrap_or_default(),
+        }
+    }
+
+    fn elements(&self) -> impl Iterator<Item = (usize, Value)> + '_ {
+        let stream = match fs::File::open(&self.path) {
+            Ok(file) => Some(JsonArrayStream::new(BufReader::new(file))),
+            Err(e) => {
+                tracing::warn!("Failed to open file {:?}: {}", self.path, e);
+                None
+            }
+        };
+        stream
+            .into_iter()
+            .flatten()
+            .map_while(|element| {
+                element
+                    .map_err(|e| tracing::warn!("Failed to parse file {:?}: {}", self.path, e))
+                    .ok()
+            })
+            .enumerate()
+    }
+
+    fn make_entity(&self, idx: usize, content: &Value) -> Option<GtsEntity> {
+        let json_file = GtsFileReader::json_file(&self.path, content);
+        let entity = GtsEntity::new(
+            Some(json_file),
+            Some(idx),
+            content,
+            Some(&self.cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        entity.gts_id.is_some().then_some(entity)
+    }
+}
+
+impl GtsReader for JsonArrayStreamReader {
+    fn iter(&mut self) -> Box<dyn Iterator<Item = GtsEntity> + '_> {
+        Box::new(
+            self.elements()
+                .filter_map(|(idx, item)| self.make_entity(idx, &item)),
+        )
+    }
+
+    /// Scans the file from the start, so each lookup reads up to the whole file.
+    fn read_by_id(&self, entity_id: &str) -> Option<GtsEntity> {
+        self.elements()
+            .filter_map(|(idx, item)| self.make_entity(idx, &item))
+            .find(|entity| {
+                entity
+                    .gts_id
+                    .as_ref()
+                    .is_some_and(|gts_id| gts_id.id == entity_id)
+            })
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Where an entity was read from: its file path (if any) and its index when the
+/// file holds an array of entities.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtsSourceLocation {
+    pub path: Option<String>,
+    pub index: Option<usize>,
+}
+
+/// Iterates all entities of `reader` and returns every GTS ID declared more than
+/// once, with the source locations of each declaration in discovery order.
+pub fn find_duplicate_ids(reader: &mut dyn GtsReader) -> HashMap<String, Vec<GtsSourceLocation>> {
+    let mut locations: HashMap<String, Vec<GtsSourceLocation>> = HashMap::new();
+    for entity in reader.iter() {
+        if let Some(gts_id) = entity.gts_id {
+            locations
+                .entry(gts_id.id)
+                .or_default()
+                .push(GtsSourceLocation {
+                    path: entity.file.map(|f| f.path),
+                    index: entity.list_sequence,
+                });
+        }
+    }
+    locations.retain(|_, locs| locs.len() > 1);
+    locations
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,8 +394,8 @@ impl GtsStore {
 
                             // Remove $id and $schema from resolved content to avoid URL resolution issues
                             if let Value::Object(ref mut resolved_map) = resolved {
-                                resolved_map.remove("$id");
-                                resolved_map.remove("$schema");
+                                resolved_map.shift_remove("$id");
+                                resolved_map.shift_remove("$schema");
                             }
 
                             // If the original object has only $ref, return the resolved schema
@@ -208,7 +443,7 @@ impl GtsStore {
         }
     }
 
-    fn remove_x_gts_ref_fields(schema: &Value) -> Value {
+    pub(crate) fn remove_x_gts_ref_fields(schema: &Value) -> Value {
         // Recursively remove x-gts-ref fields from a schema
         // This is needed because the jsonschema crate doesn't understand x-gts-ref
         // and will fail on JSON Pointer references like "/$id"
@@ -314,8 +549,8 @@ impl GtsStore {
 
         // Also remove $id and $schema to avoid URL resolution issues
         if let Value::Object(ref mut map) = schema_for_validation {
-            map.remove("$id");
-            map.remove("$schema");
+            map.shift_remove("$id");
+            map.shift_remove("$schema");
         }
 
         // For now, we'll do a basic validation by trying to compile the schema
@@ -364,8 +599,8 @@ impl GtsStore {
 
         // Remove $id and $schema from the top-level schema to avoid URL resolution issues
         if let Value::Object(ref mut map) = resolved_schema {
-            map.remove("$id");
-            map.remove("$schema");
+            map.shift_remove("$id");
+            map.shift_remove("$schema");
         }
 
         tracing::debug!(
@@ -450,12 +685,11 @@ impl GtsStore {
             (schema, schema_id.clone())
         };
 
-        // Create a resolver to handle $ref in schemas
-        // TODO: Implement custom resolver
-        let resolver = None;
+        // Resolve $ref in schemas against the schemas registered in this store
+        let resolver: &dyn SchemaResolver = &*self;
 
         from_entity
-            .cast(&to_schema, &from_schema, resolver)
+            .cast(&to_schema, &from_schema, Some(resolver))
             .map_err(|e| StoreError::SchemaNotFound(e.to_string()))
     }
 
@@ -473,7 +707,7 @@ impl GtsStore {
                 to_id: new_schema_id.to_owned(),
                 old: old_schema_id.to_owned(),
                 new: new_schema_id.to_owned(),
-                direction: "unknown".to_owned(),
+                direction: CastDirection::Unknown.to_string(),
                 added_properties: Vec::new(),
                 removed_properties: Vec::new(),
                 changed_properties: Vec::new(),
@@ -483,8 +717,11 @@ impl GtsStore {
                 incompatibility_reasons: vec!["Schema not found".to_owned()],
                 backward_errors: vec!["Schema not found".to_owned()],
                 forward_errors: vec!["Schema not found".to_owned()],
+                notices: Vec::new(),
                 casted_entity: None,
                 error: None,
+                timings: None,
+                patch: None,
             };
         };
 
@@ -498,14 +735,14 @@ impl GtsStore {
             GtsEntityCastResult::check_forward_compatibility(old_schema, new_schema);
 
         // Determine direction
-        let direction = GtsEntityCastResult::infer_direction(old_schema_id, new_schema_id);
+        let direction = CastDirection::from_ids(old_schema_id, new_schema_id);
 
         GtsEntityCastResult {
             from_id: old_schema_id.to_owned(),
             to_id: new_schema_id.to_owned(),
             old: old_schema_id.to_owned(),
             new: new_schema_id.to_owned(),
-            direction,
+            direction: direction.to_string(),
             added_properties: Vec::new(),
             removed_properties: Vec::new(),
             changed_properties: Vec::new(),
@@ -515,8 +752,11 @@ impl GtsStore {
             incompatibility_reasons: Vec::new(),
             backward_errors,
             forward_errors,
+            notices: GtsEntityCastResult::schema_notices(old_schema, new_schema),
             casted_entity: None,
             error: None,
+            timings: None,
+            patch: None,
         }
     }
 
@@ -686,13 +926,6 @@ impl GtsStore {
         is_wildcard: bool,
     ) -> (Option<GtsWildcard>, Option<GtsID>, String) {
         if is_wildcard {
-            if !base_pattern.ends_with(".*") && !base_pattern.ends_with("~*") {
-                return (
-                    None,
-                    None,
-                    "Invalid query: wildcard patterns must end with .* or ~*".to_owned(),
-                );
-            }
             match GtsWildcard::new(base_pattern) {
                 Ok(pattern) => (Some(pattern), None, String::new()),
                 Err(e) => (None, None, format!("Invalid query: {e}")),
@@ -700,7 +933,7 @@ impl GtsStore {
         } else {
             match GtsID::new(base_pattern) {
                 Ok(gts_id) => {
-                    if gts_id.gts_id_segments.is_empty() {
+                    if gts_id.segment_count() == 0 {
                         (
                             None,
                             None,
@@ -764,6 +997,15 @@ impl GtsStore {
     }
 }
 
+impl SchemaResolver for GtsStore {
+    fn resolve(&self, uri: &str) -> Option<Value> {
+        self.by_id
+            .get(uri)
+            .filter(|entity| entity.is_schema)
+            .map(|entity| entity.content.clone())
+    }
+}
+
 // Helper trait for string partitioning
 trait StringPartition {
     fn partition(&self, delimiter: char) -> (&str, &str, &str);
@@ -780,11 +1022,190 @@ impl StringPartition for str {
         }
     }
 }
+
+enum StreamState {
+    Start,
+    Elements { first: bool },
+    Done,
+}
+
+/// Pull parser yielding the elements of a JSON array one at a time. Each element is
+/// scanned to its end and parsed on its own, so only one element is in memory.
+struct JsonArrayStream<R> {
+    reader: R,
+    state: StreamState,
+}
+
+impl<R: BufRead> JsonArrayStream<R> {
+    fn new(reader: R) -> Self {
+        JsonArrayStream {
+            reader,
+            state: StreamState::Start,
+        }
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            match self.peek()? {
+                Some(b) if b.is_ascii_whitespace() => self.bump(),
+                other => return Ok(other),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> io::Result<()> {
+        match self.skip_whitespace()? {
+            Some(b) if b == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(b) => Err(invalid_data(format!(
+                "expected '{}', found '{}'",
+                expected as char, b as char
+            ))),
+            None => Err(invalid_data(format!(
+                "expected '{}', found end of file",
+                expected as char
+            ))),
+        }
+    }
+
+    /// Reads the raw bytes of the next value, leaving the reader right after it.
+    fn read_raw_value(&mut self) -> io::Result<Vec<u8>> {
+        self.skip_whitespace()?;
+        let mut raw = Vec::new();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let Some(b) = self.peek()? else {
+                if depth == 0 && !in_string && !raw.is_empty() {
+                    return Ok(raw);
+                }
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+            };
+            if in_string {
+                raw.push(b);
+                self.bump();
+                if escaped {
+                    escaped = false;
+                } else if b == b'\\' {
+                    escaped = true;
+                } else if b == b'"' {
+                    in_string = false;
+                    if depth == 0 {
+                        return Ok(raw);
+                    }
+                }
+                continue;
+            }
+            match b {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                // A top-level scalar ends at the next separator
+                _ if depth == 0 && (matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace()) => {
+                    return Ok(raw);
+                }
+                b'}' | b']' => {
+                    depth -= 1;
+                    raw.push(b);
+                    self.bump();
+                    if depth == 0 {
+                        return Ok(raw);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            raw.push(b);
+            self.bump();
+        }
+    }
+
+    /// Skips the members of a top-level object up to the start of its `entities` array.
+    fn enter_entities_member(&mut self) -> io::Result<()> {
+        let mut first = true;
+        loop {
+            if self.skip_whitespace()? == Some(b'}') {
+                return Err(invalid_data("no 'entities' array in object".to_owned()));
+            }
+            if !first {
+                self.expect(b',')?;
+            }
+            first = false;
+            let key: String = serde_json::from_slice(&self.read_raw_value()?)?;
+            self.expect(b':')?;
+            if key == "entities" {
+                return self.expect(b'[');
+            }
+            self.read_raw_value()?;
+        }
+    }
+
+    fn next_element(&mut self) -> io::Result<Option<Value>> {
+        loop {
+            match self.state {
+                StreamState::Start => match self.skip_whitespace()? {
+                    Some(b'[') => {
+                        self.bump();
+                        self.state = StreamState::Elements { first: true };
+                    }
+                    Some(b'{') => {
+                        self.bump();
+                        self.enter_entities_member()?;
+                        self.state = StreamState::Elements { first: true };
+                    }
+                    _ => return Err(invalid_data("expected a JSON array or object".to_owned())),
+                },
+                StreamState::Elements { first } => {
+                    if self.skip_whitespace()? == Some(b']') {
+                        self.bump();
+                        self.state = StreamState::Done;
+                        return Ok(None);
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                    }
+                    self.state = StreamState::Elements { first: false };
+                    let raw = self.read_raw_value()?;
+                    return Ok(Some(serde_json::from_slice(&raw)?));
+                }
+                StreamState::Done => return Ok(None),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JsonArrayStream<R> {
+    type Item = io::Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.next_element();
+        if element.is_err() {
+            self.state = StreamState::Done;
+        }
+        element.transpose()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
-    use crate::entities::{GtsConfig, GtsEntity};
+    use crate::entities::GtsFile;
+    use crate::test_utils::write_files;
     use serde_json::json;
 
     #[test]
@@ -950,6 +1371,7 @@ mod tests {
         assert_eq!(store.items().count(), 3);
 
         // Verify we can iterate
+        #[allow(clippy::needless_collect)]
         let ids: Vec<String> = store.items().map(|(id, _)| id.clone()).collect();
         assert_eq!(ids.len(), 3);
     }
@@ -1030,6 +1452,30 @@ mod tests {
         assert_eq!(result.results.len(), 3);
     }
 
+    #[test]
+    fn test_gts_store_query_mid_pattern_wildcard() {
+        let mut store = GtsStore::new(None);
+        for id in [
+            "gts.x.core.events.event.v1~",
+            "gts.x.core.audit.event.v1~",
+            "gts.x.core.audit.other.v1~",
+            "gts.y.core.audit.event.v1~",
+        ] {
+            store
+                .register_schema(id, &json!({"$id": id, "type": "object"}))
+                .expect("test");
+        }
+
+        let result = store.query("gts.x.core.*.event.v1~", 10);
+        assert!(result.error.is_empty(), "{}", result.error);
+        assert_eq!(result.count, 2);
+
+        // Patterns GtsWildcard rejects still report an error
+        let result = store.query("gts.x.core.ev*.event.v1~", 10);
+        assert!(result.error.starts_with("Invalid query:"));
+        assert_eq!(result.count, 0);
+    }
+
     #[test]
     fn test_gts_store_query_with_limit() {
         let mut store = GtsStore::new(None);
@@ -2979,6 +3425,238 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_find_duplicate_ids() {
+        let cfg = GtsConfig::default();
+        let file = GtsFile::new(
+            "/data/items.json".to_owned(),
+            "items.json".to_owned(),
+            json!([]),
+        );
+
+        let ids = [
+            "gts.vendor.package.namespace.item.v1.0",
+            "gts.vendor.package.namespace.other.v1.0",
+            "gts.vendor.package.namespace.item.v1.0",
+        ];
+        let mut entities: Vec<GtsEntity> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                GtsEntity::new(
+                    Some(file.clone()),
+                    Some(i),
+                    &json!({"id": id}),
+                    Some(&cfg),
+                    None,
+                    false,
+                    String::new(),
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        // Same ID again, this time without a source file
+        entities.push(GtsEntity::new(
+            None,
+            None,
+            &json!({"id": ids[0]}),
+            Some(&cfg),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        ));
+
+        let mut reader = MockGtsReader::new(entities);
+        let duplicates = find_duplicate_ids(&mut reader);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates[ids[0]],
+            vec![
+                GtsSourceLocation {
+                    path: Some("/data/items.json".to_owned()),
+                    index: Some(0),
+                },
+                GtsSourceLocation {
+                    path: Some("/data/items.json".to_owned()),
+                    index: Some(2),
+                },
+                GtsSourceLocation {
+                    path: None,
+                    index: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gts_memory_reader_from_values() {
+        let values = vec![
+            json!({
+                "$id": "gts.vendor.package.namespace.item.v1.0~",
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object"
+            }),
+            json!({"id": "gts.vendor.package.namespace.item.v1.0", "name": "first"}),
+            json!({"id": "gts.vendor.package.namespace.item.v1.0", "name": "second"}),
+            json!({"name": "no id"}),
+        ];
+
+        let mut reader = GtsMemoryReader::from_values(&values, &GtsConfig::default());
+
+        assert_eq!(reader.iter().count(), 3);
+        let schema = reader
+            .read_by_id("gts.vendor.package.namespace.item.v1.0~")
+            .expect("test");
+        assert!(schema.is_schema);
+        let instance = reader
+            .read_by_id("gts.vendor.package.namespace.item.v1.0")
+            .expect("test");
+        assert_eq!(instance.content["name"], "first");
+        assert!(reader
+            .read_by_id("gts.vendor.package.namespace.missing.v1.0")
+            .is_none());
+
+        reader.reset();
+        assert_eq!(reader.iter().count(), 3);
+        assert_eq!(reader.count(), 3);
+    }
+
+    #[test]
+    fn test_gts_reader_read_into_map() {
+        let values = [
+            json!({"$id": "gts.vendor.package.namespace.item.v1.0~", "type": "object"}),
+            json!({"id": "gts.vendor.package.namespace.item.v1.0", "name": "first"}),
+            json!({"id": "gts.vendor.package.namespace.item.v1.0", "name": "second"}),
+            json!({"id": "gts.vendor.package.namespace.other.v1.0"}),
+        ];
+        let mut reader = GtsMemoryReader::from_values(&values, &GtsConfig::default());
+
+        let map = reader.read_into_map();
+        let mut ids: Vec<&str> = map.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        assert_eq!(
+            ids,
+            vec![
+                "gts.vendor.package.namespace.item.v1.0",
+                "gts.vendor.package.namespace.item.v1.0~",
+                "gts.vendor.package.namespace.other.v1.0",
+            ]
+        );
+        assert!(map["gts.vendor.package.namespace.item.v1.0~"].is_schema);
+        // Unlike read_by_id, the last duplicate wins
+        assert_eq!(
+            map["gts.vendor.package.namespace.item.v1.0"].content["name"],
+            "second"
+        );
+
+        // Entities without an ID are skipped
+        let no_id = GtsEntity::new(
+            None,
+            None,
+            &json!({"name": "no id"}),
+            Some(&GtsConfig::default()),
+            None,
+            false,
+            String::new(),
+            None,
+            None,
+        );
+        assert!(no_id.gts_id.is_none());
+        let mut mock_reader = MockGtsReader::new(vec![no_id, reader.entities[1].clone()]);
+        let map = mock_reader.read_into_map();
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key("gts.vendor.package.namespace.item.v1.0"));
+    }
+
+    #[test]
+    fn test_gts_reader_count_matches_iter() {
+        let values = [
+            json!({"id": "gts.vendor.package.namespace.a.v1.0"}),
+            json!({"id": "gts.vendor.package.namespace.b.v1.0"}),
+            json!({"name": "no id"}),
+        ];
+        let memory_reader = GtsMemoryReader::from_values(&values, &GtsConfig::default());
+        let mut mock_reader = MockGtsReader::new(memory_reader.entities.clone());
+        assert_eq!(mock_reader.count(), mock_reader.iter().count());
+        assert_eq!(mock_reader.count(), 2);
+
+        let mut reader = GtsChainedReader::new(vec![
+            Box::new(memory_reader),
+            Box::new(GtsMemoryReader::new(Vec::new())),
+            Box::new(mock_reader),
+        ]);
+        assert_eq!(reader.count(), reader.iter().count());
+        assert_eq!(reader.count(), 4);
+    }
+
+    #[test]
+    fn test_gts_chained_reader_combines_file_and_memory_readers() {
+        let dir = crate::test_utils::write_files(
+            "chained_reader",
+            &[(
+                "items.json",
+                json!([
+                    {"id": "gts.vendor.package.namespace.a.v1.0", "source": "file"},
+                    {"id": "gts.vendor.package.namespace.shared.v1.0", "source": "file"}
+                ]),
+            )],
+        );
+
+        let file_reader =
+            crate::files_reader::GtsFileReader::new(&[dir.to_string_lossy().to_string()], None);
+        let memory_reader = GtsMemoryReader::from_values(
+            &[
+                json!({"id": "gts.vendor.package.namespace.shared.v1.0", "source": "memory"}),
+                json!({"id": "gts.vendor.package.namespace.b.v1.0", "source": "memory"}),
+            ],
+            &GtsConfig::default(),
+        );
+        let mut reader =
+            GtsChainedReader::new(vec![Box::new(file_reader), Box::new(memory_reader)]);
+
+        let sources: Vec<String> = reader
+            .iter()
+            .map(|e| e.content["source"].as_str().expect("test").to_owned())
+            .collect();
+        assert_eq!(sources, vec!["file", "file", "memory", "memory"]);
+
+        // The first reader wins for IDs present in both
+        let shared = reader
+            .read_by_id("gts.vendor.package.namespace.shared.v1.0")
+            .expect("test");
+        assert_eq!(shared.content["source"], "file");
+        let b = reader
+            .read_by_id("gts.vendor.package.namespace.b.v1.0")
+            .expect("test");
+        assert_eq!(b.content["source"], "memory");
+        assert!(reader
+            .read_by_id("gts.vendor.package.namespace.missing.v1.0")
+            .is_none());
+
+        reader.reset();
+        assert_eq!(reader.iter().count(), 4);
+        assert_eq!(reader.count(), 4);
+
+        std::fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_gts_store_with_memory_reader() {
+        let values = vec![
+            json!({"id": "gts.vendor.package.namespace.a.v1.0"}),
+            json!({"id": "gts.vendor.package.namespace.b.v1.0"}),
+        ];
+        let reader = GtsMemoryReader::from_values(&values, &GtsConfig::default());
+        let mut store = GtsStore::new(Some(Box::new(reader)));
+
+        assert_eq!(store.items().count(), 2);
+        assert!(store.get("gts.vendor.package.namespace.b.v1.0").is_some());
+    }
+
     #[test]
     fn test_gts_store_with_reader() {
         let cfg = GtsConfig::default();
@@ -3068,4 +3746,60 @@ mod tests {
         // Entity without gts_id should not be added to store
         assert_eq!(store.items().count(), 0);
     }
+
+    #[test]
+    fn test_json_array_reader_streams_large_array() {
+        let dir = write_files("json_array", &[]);
+        let item_id = |i: usize| format!("gts.x.core.events.event.v1~x.app.items.item_{i}.v1.0");
+        let items: Vec<Value> = (0..2_000)
+            .map(|i| json!({"id": item_id(i), "n": i, "note": "a \"quoted\" ] value"}))
+            .collect();
+        let bare = dir.join("entities.json");
+        fs::write(&bare, serde_json::to_string_pretty(&items).expect("test")).expect("test");
+        let wrapped = dir.join("export.json");
+        fs::write(
+            &wrapped,
+            serde_json::to_string(&json!({"meta": {"count": [1, 2]}, "entities": items}))
+                .expect("test"),
+        )
+        .expect("test");
+
+        for path in [&bare, &wrapped] {
+            let mut reader = JsonArrayStreamReader::new(&path.to_string_lossy(), None);
+            let mut count = 0;
+            for (i, entity) in reader.iter().enumerate() {
+                assert_eq!(entity.gts_id.expect("test").id, item_id(i));
+                assert_eq!(entity.list_sequence, Some(i));
+                assert_eq!(entity.file.expect("test").content["n"], json!(i));
+                count += 1;
+            }
+            assert_eq!(count, 2_000);
+
+            let entity = reader.read_by_id(&item_id(1_999)).expect("test");
+            assert_eq!(entity.content["n"], json!(1_999));
+            assert!(reader.read_by_id(&item_id(2_000)).is_none());
+        }
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
+
+    #[test]
+    fn test_json_array_reader_stops_at_malformed_element() {
+        let dir = write_files("json_array_broken", &[]);
+        let path = dir.join("entities.json");
+        fs::write(
+            &path,
+            r#"[{"id": "gts.x.core.events.event.v1~x.app.items.a.v1.0"}, {"id": ]"#,
+        )
+        .expect("test");
+
+        let mut reader = JsonArrayStreamReader::new(&path.to_string_lossy(), None);
+        assert_eq!(reader.iter().count(), 1);
+
+        let mut missing =
+            JsonArrayStreamReader::new(&dir.join("none.json").to_string_lossy(), None);
+        assert_eq!(missing.iter().count(), 0);
+
+        fs::remove_dir_all(&dir).expect("test");
+    }
 }
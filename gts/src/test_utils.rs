@@ -0,0 +1,21 @@
+//! Helpers shared by the unit tests of several modules.
+
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// Recreates an empty temporary directory for the test `name` and writes each
+/// `(relative path, content)` pair into it as JSON, creating parent directories.
+pub fn write_files(name: &str, files: &[(&str, Value)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gts_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("test");
+    for (file, content) in files {
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("test");
+        }
+        fs::write(path, serde_json::to_string(content).expect("test")).expect("test");
+    }
+    dir
+}